@@ -0,0 +1,87 @@
+import { test, expect } from "@playwright/test";
+import { mkdir, rm, writeFile } from "node:fs/promises";
+import path from "node:path";
+import { brotliCompressSync } from "node:zlib";
+
+const BASE_URL = "http://localhost:3000";
+/** Where cargo-leptos puts the site, which is served as static files. */
+const SITE_ROOT = path.resolve(__dirname, "../../target/site");
+
+test.describe("response compression", () => {
+  test("rendered pages are compressed with brotli", async ({ request }) => {
+    const response = await request.get(`${BASE_URL}/`, {
+      headers: { "Accept-Encoding": "br" },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(response.headers()["content-encoding"]).toBe("br");
+    expect(response.headers()["vary"]?.toLowerCase()).toContain("accept-encoding");
+  });
+
+  test("rendered pages are compressed with gzip", async ({ request }) => {
+    const response = await request.get(`${BASE_URL}/projects`, {
+      headers: { "Accept-Encoding": "gzip" },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(response.headers()["content-encoding"]).toBe("gzip");
+    expect(response.headers()["vary"]?.toLowerCase()).toContain("accept-encoding");
+  });
+
+  test("the WASM bundle is compressed", async ({ request }) => {
+    // Its name has a hash of its contents in it, which only the page knows
+    const page = await (await request.get(`${BASE_URL}/`)).text();
+    const wasm = page.match(/\/pkg\/portfolio\.[^"']*\.wasm/)?.[0];
+    expect(wasm).toBeDefined();
+
+    const response = await request.get(`${BASE_URL}${wasm}`, {
+      headers: { "Accept-Encoding": "br, gzip" },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(["br", "gzip"]).toContain(response.headers()["content-encoding"]);
+    expect(response.headers()["vary"]?.toLowerCase()).toContain("accept-encoding");
+    expect(response.headers()["cache-control"]).toContain("immutable");
+  });
+
+  test.describe("precompressed files", () => {
+    let fileName: string;
+
+    test.beforeAll(async ({}, testInfo) => {
+      // One file per worker, as every browser project runs these tests at once
+      fileName = `e2e-precompressed-${testInfo.workerIndex}.txt`;
+      await mkdir(SITE_ROOT, { recursive: true });
+      await writeFile(path.join(SITE_ROOT, fileName), "plain");
+      // Different from the plain file, to tell which of the two was sent
+      await writeFile(
+        path.join(SITE_ROOT, `${fileName}.br`),
+        brotliCompressSync("from the .br sibling"),
+      );
+    });
+
+    test.afterAll(async () => {
+      await rm(path.join(SITE_ROOT, fileName), { force: true });
+      await rm(path.join(SITE_ROOT, `${fileName}.br`), { force: true });
+    });
+
+    test("the .br sibling is sent to clients that accept brotli", async ({ request }) => {
+      const response = await request.get(`${BASE_URL}/${fileName}`, {
+        headers: { "Accept-Encoding": "br" },
+      });
+
+      expect(response.ok()).toBeTruthy();
+      expect(response.headers()["content-encoding"]).toBe("br");
+      expect(response.headers()["vary"]?.toLowerCase()).toContain("accept-encoding");
+      expect(await response.text()).toBe("from the .br sibling");
+    });
+  });
+
+  test("responses are uncompressed when the client accepts no encoding", async ({ request }) => {
+    const response = await request.get(`${BASE_URL}/`, {
+      headers: { "Accept-Encoding": "identity" },
+    });
+
+    expect(response.ok()).toBeTruthy();
+    expect(response.headers()["content-encoding"]).toBeUndefined();
+  });
+});
@@ -0,0 +1,19 @@
+-- Add unique 'slug' field to projects, derived from the project name
+ALTER TABLE projects ADD COLUMN slug TEXT;
+
+UPDATE projects
+SET slug = trim(BOTH '-' FROM regexp_replace(lower(name), '[^a-z0-9]+', '-', 'g'));
+
+-- Names without any usable characters get an id based slug
+UPDATE projects SET slug = 'project-' || id WHERE slug = '';
+
+-- Disambiguate projects whose names collapse to the same slug
+UPDATE projects p
+SET slug = p.slug || '-' || p.id
+WHERE EXISTS (
+    SELECT 1 FROM projects other
+    WHERE other.slug = p.slug AND other.id < p.id
+);
+
+ALTER TABLE projects ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE projects ADD CONSTRAINT projects_slug_key UNIQUE (slug);
@@ -0,0 +1,52 @@
+-- Move technologies out of the comma separated 'technologies' field into their own tables
+CREATE TABLE technologies (
+    id              SERIAL PRIMARY KEY,
+    slug            TEXT NOT NULL UNIQUE,
+    name            TEXT NOT NULL,
+    icon            TEXT,
+    homepage_url    TEXT
+);
+
+CREATE TABLE project_technologies (
+    project_id      INT NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    technology_id   INT NOT NULL REFERENCES technologies (id) ON DELETE CASCADE,
+    position        INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (project_id, technology_id)
+);
+
+-- Technologies that used to have a hard-coded icon
+INSERT INTO technologies (slug, name, icon, homepage_url) VALUES
+    ('python', 'Python', 'python', 'https://www.python.org'),
+    ('rust', 'Rust', 'rust', 'https://www.rust-lang.org'),
+    ('flask', 'Flask', 'flask', 'https://flask.palletsprojects.com'),
+    ('leptos', 'Leptos', 'leptos', 'https://leptos.dev'),
+    ('flutter', 'Flutter', 'flutter', 'https://flutter.dev'),
+    ('dart', 'Dart', 'dart', 'https://dart.dev'),
+    ('discord', 'Discord', 'discord', 'https://discord.com/developers'),
+    ('postgres', 'PostgreSQL', 'postgres', 'https://www.postgresql.org'),
+    ('socketio', 'Socket.IO', 'socketio', 'https://socket.io'),
+    ('opengl', 'OpenGL', 'opengl', 'https://www.opengl.org'),
+    ('javascript', 'JavaScript', 'javascript', 'https://developer.mozilla.org/en-US/docs/Web/JavaScript'),
+    ('rocket', 'Rocket', 'rocket', 'https://rocket.rs'),
+    ('firebase', 'Firebase', 'firebase', 'https://firebase.google.com'),
+    ('swift', 'Swift', 'swift', 'https://www.swift.org'),
+    ('redis', 'Redis', 'redis', 'https://redis.io'),
+    ('supabase', 'Supabase', 'supabase', 'https://supabase.com'),
+    ('arkit', 'ARKit', 'arkit', 'https://developer.apple.com/augmented-reality/arkit');
+
+-- Any other technology mentioned by a project, without an icon
+INSERT INTO technologies (slug, name)
+SELECT DISTINCT lower(trim(tech)), initcap(trim(tech))
+FROM projects, unnest(string_to_array(technologies, ',')) AS tech
+WHERE trim(tech) <> ''
+ON CONFLICT (slug) DO NOTHING;
+
+-- Link projects to their technologies, keeping the order they were listed in
+INSERT INTO project_technologies (project_id, technology_id, position)
+SELECT p.id, t.id, min(tech.position)
+FROM projects p
+CROSS JOIN unnest(string_to_array(p.technologies, ',')) WITH ORDINALITY AS tech (name, position)
+JOIN technologies t ON t.slug = lower(trim(tech.name))
+GROUP BY p.id, t.id;
+
+ALTER TABLE projects DROP COLUMN technologies;
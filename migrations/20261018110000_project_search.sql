@@ -0,0 +1,8 @@
+-- Add a full-text search vector over project names and descriptions
+ALTER TABLE projects ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', name), 'A') ||
+    setweight(to_tsvector('english', short_description), 'B') ||
+    setweight(to_tsvector('english', long_description), 'C')
+) STORED;
+
+CREATE INDEX projects_search_vector_idx ON projects USING GIN (search_vector);
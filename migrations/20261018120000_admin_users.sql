@@ -0,0 +1,15 @@
+-- Admin accounts and their login sessions
+CREATE TABLE admin_users (
+    id              SERIAL PRIMARY KEY,
+    username        TEXT NOT NULL UNIQUE,
+    password_hash   TEXT NOT NULL,
+    date_created    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE admin_sessions (
+    token           TEXT PRIMARY KEY,
+    admin_user_id   INT NOT NULL REFERENCES admin_users (id) ON DELETE CASCADE,
+    expires_at      TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX admin_sessions_admin_user_id_idx ON admin_sessions (admin_user_id);
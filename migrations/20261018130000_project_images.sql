@@ -0,0 +1,9 @@
+-- Processed variants of each project's image
+CREATE TABLE project_images (
+    project_id      INT NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    width           INT NOT NULL,
+    height          INT NOT NULL,
+    format          TEXT NOT NULL,
+    path            TEXT NOT NULL,
+    PRIMARY KEY (project_id, width, format)
+);
@@ -0,0 +1,2 @@
+-- Add a tiny preview of the project image, shown while the full image loads
+ALTER TABLE projects ADD COLUMN image_placeholder TEXT;
@@ -0,0 +1,14 @@
+-- Add blog posts table
+CREATE TABLE posts (
+    id              SERIAL PRIMARY KEY,
+    slug            TEXT NOT NULL UNIQUE,
+    title           TEXT NOT NULL,
+    summary         TEXT NOT NULL DEFAULT '',
+    body            TEXT NOT NULL,
+    tags            TEXT[] NOT NULL DEFAULT '{}',
+    published       BOOL NOT NULL DEFAULT false,
+    published_at    TIMESTAMPTZ,
+    date_created    DATE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX posts_published_at_idx ON posts (published_at DESC) WHERE published;
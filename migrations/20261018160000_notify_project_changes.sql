@@ -0,0 +1,23 @@
+-- Notify listeners when projects or anything shown with them changes, so caches can be cleared
+CREATE FUNCTION notify_projects_changed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('projects_changed', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER projects_changed
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON projects
+FOR EACH STATEMENT EXECUTE FUNCTION notify_projects_changed();
+
+CREATE TRIGGER projects_changed
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON project_technologies
+FOR EACH STATEMENT EXECUTE FUNCTION notify_projects_changed();
+
+CREATE TRIGGER projects_changed
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON project_images
+FOR EACH STATEMENT EXECUTE FUNCTION notify_projects_changed();
+
+CREATE TRIGGER projects_changed
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON technologies
+FOR EACH STATEMENT EXECUTE FUNCTION notify_projects_changed();
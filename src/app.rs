@@ -2,47 +2,70 @@ use crate::error_template::{AppError, ErrorTemplate};
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
+use serde::{Deserialize, Serialize};
 
+pub mod admin;
+pub mod blog;
 mod box_collision;
 mod home;
-mod projects;
+mod page_meta;
+pub mod projects;
+mod structured_data;
 
+use admin::{AdminArea, AdminDashboard, AdminEditProject, AdminLogin, AdminNewProject};
+use blog::{Blog, BlogPost};
 use box_collision::BoxCollision;
 use home::HomePage;
-use projects::Projects;
+use projects::{ProjectDetail, Projects};
+
+/// Public address of the site, for links that leave it (feeds, share metadata).
+pub const SITE_URL: &str = "https://codeboi.dev";
+
+/// Analytics script included on every page.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Analytics {
+    pub script_url: String,
+    pub website_id: String,
+}
 
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
 
-    let meta_description = "Hi there! I'm Rehatbir, and welcome to my humble abode on the internet :D I am a high school student who likes to code, play guitar, and loves learning.";
+    // The config is only around on the server, which puts the script in the
+    // rendered <head> where it stays after hydration
+    #[cfg(feature = "ssr")]
+    let analytics = use_context::<std::sync::Arc<crate::config::Config>>()
+        .and_then(|config| config.analytics.clone());
+    #[cfg(not(feature = "ssr"))]
+    let analytics: Option<Analytics> = None;
+
+    // Likewise for the stylesheet, as only the server knows the hash in its name
+    #[cfg(feature = "ssr")]
+    let stylesheet = use_context::<LeptosOptions>()
+        .map(|options| view! { <HashedStylesheet id="leptos" options/> }.into_view());
+    #[cfg(not(feature = "ssr"))]
+    let stylesheet: Option<View> = None;
 
     view! {
-        <Stylesheet id="leptos" href="/pkg/portfolio.css"/>
+        {stylesheet}
+        <Stylesheet id="highlight" href="/highlight.css"/>
 
-        <Meta name="og:title" content="CodeBoi"/>
-        <Meta name="og:image" content="/images/CB.png"/>
-        <Meta name="og:url" content="https://codeboi.dev"/>
         <Meta name="theme-color" content="#09e85e"/>
-        <Meta name="og:description" content=meta_description/>
-        <Meta name="description" content=meta_description/>
+
+        <Link
+            rel="alternate"
+            type_="application/atom+xml"
+            title="CodeBoi (Atom)"
+            href="/feed.xml"
+        />
+        <Link rel="alternate" type_="application/rss+xml" title="CodeBoi (RSS)" href="/rss.xml"/>
 
         <Link rel="preload" as_="font" href="/fonts/UbuntuMono-Regular.ttf"/>
         <Link rel="preload" as_="font" href="/fonts/UbuntuMono-Bold.ttf"/>
         <Link rel="preload" as_="font" href="/fonts/UbuntuMono-Italic.ttf"/>
         <Link rel="preload" as_="font" href="/fonts/UbuntuMono-BoldItalic.ttf"/>
 
-        <Script
-            async_="true"
-            src="https://umami.codeboi.dev/script.js"
-            attrs=vec![
-                (
-                    "data-website-id",
-                    Attribute::String("6d05defe-708b-41d7-91c1-40a55ec94f3d".into()),
-                ),
-            ]
-        />
-
         <svg class="bg-design">
             <radialGradient id="Gradient1">
                 <stop offset="0%" stop-color="#00ff61"></stop>
@@ -62,7 +85,26 @@ pub fn App() -> impl IntoView {
                     <Routes>
                         <Route path="" view=|| view! { <HomePage/> }/>
                         <Route path="/projects" view=|| view! { <Projects/> }/>
+                        // Rendered in async mode so a missing project can set a 404 status
+                        // (or an old id based link its redirect) before the response headers are sent
+                        <Route
+                            path="/projects/:slug"
+                            view=|| view! { <ProjectDetail/> }
+                            ssr=SsrMode::Async
+                        />
                         <Route path="/box" view=|| view! { <BoxCollision/> }/>
+                        <Route path="/blog" view=|| view! { <Blog/> }/>
+                        <Route
+                            path="/blog/:slug"
+                            view=|| view! { <BlogPost/> }
+                            ssr=SsrMode::Async
+                        />
+                        <Route path="/admin/login" view=|| view! { <AdminLogin/> }/>
+                        <Route path="/admin" view=|| view! { <AdminArea/> } ssr=SsrMode::Async>
+                            <Route path="" view=|| view! { <AdminDashboard/> }/>
+                            <Route path="projects/new" view=|| view! { <AdminNewProject/> }/>
+                            <Route path="projects/:id" view=|| view! { <AdminEditProject/> }/>
+                        </Route>
                     </Routes>
                 </Router>
             </main>
@@ -75,5 +117,18 @@ pub fn App() -> impl IntoView {
                 View Source on GitHub
             </a>
         </footer>
+
+        // Last, so that rendering it on the server only doesn't throw off the
+        // hydration of anything after it
+        {analytics
+            .map(|analytics| {
+                view! {
+                    <Script
+                        async_="true"
+                        src=analytics.script_url
+                        attrs=vec![("data-website-id", Attribute::String(analytics.website_id.into()))]
+                    />
+                }
+            })}
     }
 }
@@ -0,0 +1,533 @@
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+use stylers::style;
+
+use crate::app::projects::{Project, ProjectImage};
+
+/// Fields of the project editor, as submitted by its form.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProjectForm {
+    pub slug: String,
+    pub name: String,
+    pub short_description: String,
+    pub long_description: String,
+    /// Left blank for projects without an external link.
+    pub url: String,
+    /// Comma separated technology slugs, in display order.
+    pub technologies: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl ProjectForm {
+    pub fn url(&self) -> Option<&str> {
+        Some(self.url.trim()).filter(|url| !url.is_empty())
+    }
+
+    pub fn technology_slugs(&self) -> Vec<String> {
+        let mut slugs: Vec<String> = Vec::new();
+        for slug in self.technologies.split(',') {
+            let slug = slug.trim().to_lowercase();
+            if !slug.is_empty() && !slugs.contains(&slug) {
+                slugs.push(slug);
+            }
+        }
+        slugs
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let slug = self.slug.trim();
+        if slug.is_empty()
+            || !slug
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-')
+        {
+            return Err("Slugs may only contain lowercase letters, digits and dashes.".into());
+        }
+        // Numeric slugs would be mistaken for the ids of old project links
+        if slug.parse::<i32>().is_ok() {
+            return Err("Slugs cannot be a plain number.".into());
+        }
+        if self.name.trim().is_empty() {
+            return Err("Projects need a name.".into());
+        }
+        Ok(())
+    }
+}
+
+impl From<&Project> for ProjectForm {
+    fn from(project: &Project) -> Self {
+        Self {
+            slug: project.slug.clone(),
+            name: project.name.clone(),
+            short_description: project.short_description.clone(),
+            long_description: project.long_description.clone(),
+            url: project.url.clone().unwrap_or_default(),
+            technologies: project
+                .technologies
+                .iter()
+                .map(|tech| tech.slug.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            pinned: project.pinned,
+        }
+    }
+}
+
+/// Drops cached projects, so the public pages show a change right away.
+#[cfg(feature = "ssr")]
+fn invalidate_projects() {
+    if let Some(store) = use_context::<crate::store::SharedProjectStore>() {
+        store.invalidate();
+    }
+}
+
+/// Username of the logged in admin, if any.
+#[server(GetAdminSession)]
+pub async fn get_admin_session() -> Result<Option<String>, ServerFnError> {
+    use sqlx::PgPool;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    let admin = crate::auth::current_admin(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+    Ok(admin.map(|admin| admin.username))
+}
+
+#[server(Login)]
+pub async fn login(username: String, password: String) -> Result<(), ServerFnError> {
+    use sqlx::PgPool;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    let admin = crate::auth::authenticate(&pool, &username, &password)
+        .await
+        .map_err(|e| ServerFnError::new(e))?
+        .ok_or(ServerFnError::new("Invalid username or password."))?;
+
+    let token = crate::auth::create_session(&pool, admin.id)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+    crate::auth::set_session_cookie(&token);
+
+    leptos_axum::redirect("/admin");
+    Ok(())
+}
+
+#[server(Logout)]
+pub async fn logout() -> Result<(), ServerFnError> {
+    use sqlx::PgPool;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    if let Some(token) = crate::auth::session_token() {
+        crate::auth::delete_session(&pool, &token)
+            .await
+            .map_err(|e| ServerFnError::new(e))?;
+    }
+    crate::auth::clear_session_cookie();
+
+    leptos_axum::redirect("/admin/login");
+    Ok(())
+}
+
+#[server(GetAdminProjects)]
+pub async fn get_admin_projects() -> Result<Vec<Project>, ServerFnError> {
+    use sqlx::PgPool;
+    crate::auth::require_admin().await?;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    crate::db::get_projects(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(e))
+}
+
+/// A project by id, for the editor. Unlike `get_project`, it reads straight
+/// from the database, which is what the editor saves to.
+#[server(GetAdminProject)]
+pub async fn get_admin_project(id: i32) -> Result<Option<Project>, ServerFnError> {
+    use sqlx::PgPool;
+    crate::auth::require_admin().await?;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    crate::db::get_project_by_id(&pool, id)
+        .await
+        .map_err(|e| ServerFnError::new(e))
+}
+
+#[server(CreateProject)]
+pub async fn create_project(project: ProjectForm) -> Result<(), ServerFnError> {
+    use sqlx::PgPool;
+    crate::auth::require_admin().await?;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    project.validate().map_err(ServerFnError::new)?;
+    crate::db::insert_project(&pool, &project)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+    invalidate_projects();
+
+    leptos_axum::redirect("/admin");
+    Ok(())
+}
+
+#[server(UpdateProject)]
+pub async fn update_project(id: i32, project: ProjectForm) -> Result<(), ServerFnError> {
+    use sqlx::PgPool;
+    crate::auth::require_admin().await?;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    project.validate().map_err(ServerFnError::new)?;
+    let updated = crate::db::update_project(&pool, id, &project)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+    if !updated {
+        return Err(ServerFnError::new("Project not found."));
+    }
+    invalidate_projects();
+
+    leptos_axum::redirect("/admin");
+    Ok(())
+}
+
+#[server(DeleteProject)]
+pub async fn delete_project(id: i32) -> Result<(), ServerFnError> {
+    use sqlx::PgPool;
+    crate::auth::require_admin().await?;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    let deleted = crate::db::delete_project(&pool, id)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+    if !deleted {
+        return Err(ServerFnError::new("Project not found."));
+    }
+    invalidate_projects();
+
+    Ok(())
+}
+
+#[server(SetProjectPinned)]
+pub async fn set_project_pinned(id: i32, pinned: bool) -> Result<(), ServerFnError> {
+    use sqlx::PgPool;
+    crate::auth::require_admin().await?;
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    let updated = crate::db::set_project_pinned(&pool, id, pinned)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+    if !updated {
+        return Err(ServerFnError::new("Project not found."));
+    }
+    invalidate_projects();
+
+    Ok(())
+}
+
+/// Wraps every admin page, sending visitors who are not logged in to the login page.
+#[component]
+pub fn AdminArea() -> impl IntoView {
+    let session = create_blocking_resource(|| (), |_| get_admin_session());
+    let logout = create_server_action::<Logout>();
+
+    view! {
+        <Title text="CodeBoi's Admin"/>
+
+        <div class="admin">
+            <Transition fallback=move || view! { <p>"Loading..."</p> }>
+                {move || {
+                    session
+                        .get()
+                        .map(|session| {
+                            match session {
+                                Ok(Some(username)) => {
+                                    view! {
+                                        <ActionForm action=logout>
+                                            <span class="muted">"Logged in as " {username} " "</span>
+                                            <button type="submit">"Log out"</button>
+                                        </ActionForm>
+                                        <Outlet/>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(None) => view! { <Redirect path="/admin/login"/> }.into_view(),
+                                Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
+                            }
+                        })
+                }}
+
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminLogin() -> impl IntoView {
+    let login = create_server_action::<Login>();
+
+    let style_class = style! {
+        .login-form {
+            display: flex;
+            flex-direction: column;
+            gap: 8px;
+        }
+    };
+
+    view! { class=style_class,
+        <Title text="CodeBoi's Admin"/>
+
+        <h2 align="center">"Admin Login"</h2>
+        <div class="admin content content-border">
+            <ActionForm action=login>
+                <div class="login-form">
+                    <label>
+                        "Username" <input type="text" name="username" autocomplete="username" required/>
+                    </label>
+                    <label>
+                        "Password"
+                        <input
+                            type="password"
+                            name="password"
+                            autocomplete="current-password"
+                            required
+                        />
+                    </label>
+                    <button type="submit">"Log in"</button>
+                </div>
+            </ActionForm>
+            {move || {
+                login.value().get().and_then(Result::err).map(|e| view! { <p>{e.to_string()}</p> })
+            }}
+
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminDashboard() -> impl IntoView {
+    let set_pinned = create_server_action::<SetProjectPinned>();
+    let delete = create_server_action::<DeleteProject>();
+    let projects = create_resource(
+        move || (set_pinned.version().get(), delete.version().get()),
+        |_| get_admin_projects(),
+    );
+
+    let style_class = style! {
+        .admin-projects {
+            width: 100%;
+            border-collapse: collapse;
+        }
+
+        .admin-projects td {
+            padding: 6px 4px;
+            border-top: 1px solid rgba(255, 255, 255, 0.24);
+        }
+    };
+
+    view! { class=style_class,
+        <h2 align="center">"Manage Projects"</h2>
+        <a href="/admin/projects/new">"+ New Project"</a>
+
+        <div class="content content-border">
+            <Transition fallback=move || view! { <p>"Loading..."</p> }>
+                {move || {
+                    let error = move || {
+                        set_pinned
+                            .value()
+                            .get()
+                            .or_else(|| delete.value().get())
+                            .and_then(Result::err)
+                            .map(|e| view! { <p>{e.to_string()}</p> })
+                    };
+
+                    projects
+                        .get()
+                        .map(|projects| {
+                            match projects {
+                                Ok(projects) => {
+                                    view! { class=style_class,
+                                        {error}
+                                        <table class="admin-projects">
+                                            {projects
+                                                .into_iter()
+                                                .map(|project| {
+                                                    view! { class=style_class,
+                                                        <tr>
+                                                            <td>
+                                                                <a href=format!(
+                                                                    "/admin/projects/{}",
+                                                                    project.id,
+                                                                )>{project.name}</a>
+                                                            </td>
+                                                            <td class="muted">{project.date_created.to_string()}</td>
+                                                            <td>
+                                                                <ActionForm action=set_pinned>
+                                                                    <input type="hidden" name="id" value=project.id/>
+                                                                    <input
+                                                                        type="hidden"
+                                                                        name="pinned"
+                                                                        value=(!project.pinned).to_string()
+                                                                    />
+                                                                    <button type="submit">
+                                                                        {if project.pinned { "Unpin" } else { "Pin" }}
+                                                                    </button>
+                                                                </ActionForm>
+                                                            </td>
+                                                            <td>
+                                                                <ActionForm action=delete>
+                                                                    <input type="hidden" name="id" value=project.id/>
+                                                                    <button type="submit">"Delete"</button>
+                                                                </ActionForm>
+                                                            </td>
+                                                        </tr>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                        </table>
+                                    }
+                                        .into_view()
+                                }
+                                Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
+                            }
+                        })
+                }}
+
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminNewProject() -> impl IntoView {
+    let create = create_server_action::<CreateProject>();
+
+    view! {
+        <h2 align="center">"New Project"</h2>
+        <div class="content content-border">
+            <ActionForm action=create>
+                <ProjectFields project=ProjectForm::default()/>
+                <button type="submit">"Create"</button>
+            </ActionForm>
+            {move || {
+                create.value().get().and_then(Result::err).map(|e| view! { <p>{e.to_string()}</p> })
+            }}
+
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminEditProject() -> impl IntoView {
+    let params = use_params_map();
+    // An id that doesn't parse matches no project
+    let project = create_resource(
+        move || params.with(|p| p.get("id").and_then(|id| id.parse::<i32>().ok()).unwrap_or_default()),
+        get_admin_project,
+    );
+    let update = create_server_action::<UpdateProject>();
+
+    view! {
+        <h2 align="center">"Edit Project"</h2>
+        <div class="content content-border">
+            <Transition fallback=move || view! { <p>"Loading..."</p> }>
+                {move || {
+                    project
+                        .get()
+                        .map(|project| {
+                            match project {
+                                Ok(Some(project)) => {
+                                    view! {
+                                        <ActionForm action=update>
+                                            <input type="hidden" name="id" value=project.id/>
+                                            <ProjectFields project=ProjectForm::from(&project)/>
+                                            <button type="submit">"Save"</button>
+                                        </ActionForm>
+
+                                        <h3>"Image"</h3>
+                                        <ProjectImage project=&project sizes="800px"/>
+                                        // A plain form, since the upload is handled by axum rather than a server function
+                                        <form
+                                            method="post"
+                                            enctype="multipart/form-data"
+                                            action=format!("/admin/projects/{}/image", project.id)
+                                        >
+                                            <input
+                                                type="file"
+                                                name="image"
+                                                accept="image/png,image/jpeg,image/webp"
+                                                required
+                                            />
+                                            <button type="submit">"Upload"</button>
+                                        </form>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(None) => view! { <p>"Project not found."</p> }.into_view(),
+                                Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
+                            }
+                        })
+                }}
+
+            </Transition>
+            {move || {
+                update.value().get().and_then(Result::err).map(|e| view! { <p>{e.to_string()}</p> })
+            }}
+
+        </div>
+    }
+}
+
+/// Inputs for every [`ProjectForm`] field, named so they deserialize into the
+/// `project` argument of [`create_project`] and [`update_project`].
+#[component]
+fn ProjectFields(project: ProjectForm) -> impl IntoView {
+    let style_class = style! {
+        .project-fields {
+            display: flex;
+            flex-direction: column;
+            gap: 8px;
+            margin-bottom: 12px;
+        }
+
+        .project-fields label {
+            display: flex;
+            flex-direction: column;
+        }
+
+        .project-fields textarea {
+            min-height: 12em;
+        }
+    };
+
+    view! { class=style_class,
+        <div class="project-fields">
+            <label>
+                "Name" <input type="text" name="project[name]" value=project.name required/>
+            </label>
+            <label>
+                "Slug" <input type="text" name="project[slug]" value=project.slug required/>
+            </label>
+            <label>
+                "Short Description"
+                <input type="text" name="project[short_description]" value=project.short_description/>
+            </label>
+            <label>
+                "Long Description"
+                <textarea name="project[long_description]">{project.long_description}</textarea>
+            </label>
+            <label>
+                "URL" <input type="url" name="project[url]" value=project.url/>
+            </label>
+            <label>
+                "Technologies (comma separated slugs)"
+                <input type="text" name="project[technologies]" value=project.technologies/>
+            </label>
+            <label>
+                <span>
+                    <input type="checkbox" name="project[pinned]" value="true" checked=project.pinned/>
+                    " Pinned"
+                </span>
+            </label>
+        </div>
+    }
+}
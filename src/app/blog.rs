@@ -0,0 +1,231 @@
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+use stylers::style;
+use time::{Date, OffsetDateTime};
+
+use crate::app::page_meta::PageMeta;
+use crate::error_template::{AppError, ErrorTemplate};
+
+#[derive(Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Post {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub summary: String,
+    /// Markdown source of the post.
+    pub body: String,
+    /// `body` rendered to sanitized HTML on the server.
+    pub body_html: String,
+    pub tags: Vec<String>,
+    pub published: bool,
+    pub published_at: Option<OffsetDateTime>,
+    pub date_created: Date,
+}
+
+impl Post {
+    /// The day the post was published, or created if it is still a draft.
+    pub fn date(&self) -> Date {
+        self.published_at
+            .map(|published_at| published_at.date())
+            .unwrap_or(self.date_created)
+    }
+}
+
+/// Published posts, newest first. Returns every post unless `limit` is given,
+/// which is clamped to `1..=50` as it comes from the client.
+///
+/// Posts only live in the database, so a site served purely from a content
+/// directory has none.
+#[server(GetPosts, "/api", "GetJson")]
+pub async fn get_posts(limit: Option<i64>) -> Result<Vec<Post>, ServerFnError> {
+    use sqlx::PgPool;
+    /// Most posts a single request may ask for.
+    const MAX_LIMIT: i64 = 50;
+
+    let Some(pool) = use_context::<PgPool>() else {
+        return Ok(Vec::new());
+    };
+
+    let limit = limit.map(|limit| limit.clamp(1, MAX_LIMIT));
+    crate::db::get_posts(&pool, limit)
+        .await
+        .map_err(|e| ServerFnError::new(e))
+}
+
+/// Looks up a published post by its slug.
+#[server(GetPost, "/api", "GetJson")]
+pub async fn get_post(slug: String) -> Result<Option<Post>, ServerFnError> {
+    use sqlx::PgPool;
+    let Some(pool) = use_context::<PgPool>() else {
+        return Ok(None);
+    };
+
+    crate::db::get_post(&pool, &slug)
+        .await
+        .map_err(|e| ServerFnError::new(e))
+}
+
+#[component]
+pub fn Blog() -> impl IntoView {
+    let posts = create_resource(|| (), |_| get_posts(None));
+
+    view! {
+        <PageMeta
+            title="CodeBoi's Blog"
+            description="Posts and write-ups by CodeBoi."
+            path="/blog"
+        />
+
+        <a href="/" class="muted">
+            "< Back"
+        </a>
+
+        <h2 align="center">Blog</h2>
+        <div class="posts">
+            <Transition fallback=move || view! { <p>"Loading..."</p> }>
+                <ErrorBoundary fallback=move |_| {
+                    view! { <p>"oops"</p> }
+                }>
+                    {move || {
+                        posts
+                            .get()
+                            .map(|posts| {
+                                match posts {
+                                    Ok(posts) if posts.is_empty() => {
+                                        view! { <p align="center">"Nothing here yet, check back soon!"</p> }
+                                            .into_view()
+                                    }
+                                    Ok(posts) => {
+                                        view! {
+                                            <For
+                                                each=move || posts.clone()
+                                                key=|p| p.id
+                                                children=move |post| {
+                                                    view! { <PostCard post=&post/> }
+                                                }
+                                            />
+                                        }
+                                    }
+                                    Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
+                                }
+                            })
+                    }}
+
+                </ErrorBoundary>
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+pub fn PostCard<'a>(post: &'a Post) -> impl IntoView {
+    let style_class = style! {
+        .post-card {
+            margin: 16px 0px;
+        }
+
+        .post-card h3 {
+            margin-bottom: 4px;
+        }
+    };
+
+    view! { class=style_class,
+        <div class="post-card content content-border">
+            <h3>
+                <a href=format!("/blog/{}", post.slug)>{&post.title}</a>
+            </h3>
+            <PostMeta post/>
+            <p>{&post.summary}</p>
+        </div>
+    }
+}
+
+/// Publishing date and tags of a post.
+#[component]
+fn PostMeta<'a>(post: &'a Post) -> impl IntoView {
+    let style_class = style! {
+        .post-meta {
+            display: flex;
+            flex-wrap: wrap;
+            gap: 8px;
+            font-size: 0.9rem;
+        }
+
+        .tag {
+            border: 1px solid rgba(255, 255, 255, 0.24);
+            border-radius: 4px;
+            padding: 0px 4px;
+        }
+    };
+
+    let tags = post
+        .tags
+        .iter()
+        .map(|tag| view! { class=style_class, <span class="tag">{tag}</span> })
+        .collect_view();
+
+    view! { class=style_class,
+        <div class="post-meta muted">
+            <span>{post.date().to_string()}</span>
+            {tags}
+        </div>
+    }
+}
+
+#[component]
+pub fn BlogPost() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.with(|p| p.get("slug").cloned().unwrap_or_default());
+    let post = create_resource(slug, get_post);
+
+    let style_class = style! {
+        .post {
+            margin: 16px 0px;
+        }
+    };
+
+    view! { class=style_class,
+        <a href="/blog" class="muted">
+            "< Back"
+        </a>
+
+        <Transition fallback=move || view! { <p>"Loading..."</p> }>
+            <ErrorBoundary fallback=|_| {
+                view! { <p>"oops"</p> }
+            }>
+                {move || {
+                    post.get()
+                        .map(|post| {
+                            match post {
+                                Ok(Some(post)) => {
+                                    view! { class=style_class,
+                                        <PageMeta
+                                            title=format!("{} | CodeBoi's Blog", post.title)
+                                            description=post.summary.clone()
+                                            path=format!("/blog/{}", post.slug)
+                                            og_type="article"
+                                        />
+
+                                        <h2 align="center">{&post.title}</h2>
+                                        <div class="post content content-border">
+                                            <PostMeta post=&post/>
+                                            <div class="markdown" inner_html=post.body_html.clone()></div>
+                                        </div>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(None) => {
+                                    let mut outside_errors = Errors::default();
+                                    outside_errors.insert_with_default_key(AppError::NotFound);
+                                    view! { <ErrorTemplate outside_errors/> }.into_view()
+                                }
+                                Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
+                            }
+                        })
+                }}
+
+            </ErrorBoundary>
+        </Transition>
+    }
+}
@@ -1,7 +1,8 @@
 use leptos::*;
-use leptos_meta::*;
 use stylers::style;
 
+use crate::app::page_meta::PageMeta;
+
 #[component]
 pub fn BoxCollision() -> impl IntoView {
     let style_class = style! {
@@ -11,7 +12,11 @@ pub fn BoxCollision() -> impl IntoView {
     };
 
     view! { class=style_class,
-        <Title text="Box Collision"/>
+        <PageMeta
+            title="Box Collision"
+            description="A simulation of two colliding boxes whose number of collisions spells out the digits of π."
+            path="/box"
+        />
         <script src="/wasm/pi_estimator/load.js" type="module" defer></script>
 
         <a href="/" class="muted">
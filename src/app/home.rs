@@ -1,14 +1,63 @@
 use icondata as i;
 use leptos::*;
 use leptos_icons::*;
-use leptos_meta::*;
 use stylers::style;
 
+use crate::app::blog::{get_posts, PostCard};
+use crate::app::page_meta::PageMeta;
 use crate::app::projects::{get_pinned_projects, PinnedProjectCard};
+use crate::app::structured_data::{JsonLd, Person, OWNER_NAME};
+use crate::app::SITE_URL;
+
+/// A profile of CodeBoi on another site, linked from the home page.
+struct Social {
+    name: &'static str,
+    url: &'static str,
+    icon: i::Icon,
+}
+
+const SOCIALS: [Social; 4] = [
+    Social {
+        name: "GitHub",
+        url: "https://github.com/mysterycoder456",
+        icon: i::BsGithub,
+    },
+    Social {
+        name: "MonkeyType",
+        url: "https://monkeytype.com/profile/CodeBoi",
+        icon: i::BsKeyboardFill,
+    },
+    Social {
+        name: "LinkedIn",
+        url: "https://www.linkedin.com/in/rehatbir-singh-4805ba193",
+        icon: i::BsLinkedin,
+    },
+    Social {
+        name: "Fiverr",
+        url: "https://www.fiverr.com/rehatbirsingh",
+        icon: i::SiFiverr,
+    },
+];
+
+const EMAIL: &str = "rehatbir.singh@gmail.com";
+
+/// Number of posts shown in the "Latest Posts" section.
+const LATEST_POSTS: i64 = 3;
 
 #[component]
 pub fn HomePage() -> impl IntoView {
     let projects = create_resource(|| (), move |_| get_pinned_projects());
+    let posts = create_resource(|| (), move |_| get_posts(Some(LATEST_POSTS)));
+
+    let person = Person {
+        kind: "Person",
+        name: OWNER_NAME,
+        alternate_name: "CodeBoi",
+        url: SITE_URL,
+        image: format!("{SITE_URL}/images/CB.png"),
+        email: EMAIL,
+        same_as: SOCIALS.iter().map(|social| social.url).collect(),
+    };
 
     let style_class = style! {
         .codeboi-pfp {
@@ -35,7 +84,12 @@ pub fn HomePage() -> impl IntoView {
     };
 
     view! { class=style_class,
-        <Title text="CodeBoi"/>
+        <PageMeta
+            title="CodeBoi"
+            description="Hi there! I'm Rehatbir, and welcome to my humble abode on the internet :D I am a high school student who likes to code, play guitar, and loves learning."
+            path="/"
+        />
+        <JsonLd data=person/>
 
         <div align="center">
             <img
@@ -49,27 +103,18 @@ pub fn HomePage() -> impl IntoView {
         </div>
 
         <div class="socials" align="center">
-            <a href="https://github.com/mysterycoder456" title="GitHub" target="_blank" class="muted">
-                <Icon icon=i::BsGithub width="40px" height="40px"/>
-            </a>
-
-            <a href="https://monkeytype.com/profile/CodeBoi" title="MonkeyType" target="_blank" class="muted">
-                <Icon icon=i::BsKeyboardFill width="40px" height="40px"/>
-            </a>
-
-            <a
-                href="https://www.linkedin.com/in/rehatbir-singh-4805ba193"
-                title="LinkedIn"
-                target="_blank" class="muted"
-            >
-                <Icon icon=i::BsLinkedin width="40px" height="40px"/>
-            </a>
-
-            <a href="https://www.fiverr.com/rehatbirsingh" title="Fiverr" target="_blank" class="muted">
-                <Icon icon=i::SiFiverr width="40px" height="40px"/>
-            </a>
-
-            <a href="mailto:rehatbir.singh@gmail.com" title="Mail" target="_blank" class="muted">
+            {SOCIALS
+                .iter()
+                .map(|social| {
+                    view! { class=style_class,
+                        <a href=social.url title=social.name target="_blank" class="muted">
+                            <Icon icon=social.icon width="40px" height="40px"/>
+                        </a>
+                    }
+                })
+                .collect_view()}
+
+            <a href=format!("mailto:{EMAIL}") title="Mail" target="_blank" class="muted">
                 <Icon icon=i::TbMailFilled width="40px" height="40px"/>
             </a>
         </div>
@@ -141,5 +186,45 @@ pub fn HomePage() -> impl IntoView {
                 <h3>View More</h3>
             </a>
         </div>
+
+        <h2>"Latest Posts"</h2>
+        <div class="latest-posts">
+            <Transition fallback=move || view! { <p>"Loading..."</p> }>
+                <ErrorBoundary fallback=|_| {
+                    view! { <p>"oops"</p> }
+                }>
+                    {move || {
+                        posts
+                            .get()
+                            .map(|posts| {
+                                match posts {
+                                    Ok(posts) if posts.is_empty() => {
+                                        view! { <p align="center">"Nothing here yet, check back soon!"</p> }
+                                            .into_view()
+                                    }
+                                    Ok(posts) => {
+                                        view! {
+                                            <For
+                                                each=move || posts.clone()
+                                                key=|p| p.id
+                                                children=move |post| {
+                                                    view! { <PostCard post=&post/> }
+                                                }
+                                            />
+                                        }
+                                    }
+                                    Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
+                                }
+                            })
+                    }}
+
+                </ErrorBoundary>
+            </Transition>
+        </div>
+        <div align="center">
+            <a href="/blog">
+                <h3>Read More</h3>
+            </a>
+        </div>
     }
 }
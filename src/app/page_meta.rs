@@ -0,0 +1,58 @@
+use leptos::*;
+use leptos_meta::*;
+
+use crate::app::SITE_URL;
+
+/// Image used for link previews of pages without one of their own.
+const DEFAULT_IMAGE: &str = "/images/CB.png";
+
+fn absolute_url(path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_owned()
+    } else {
+        format!("{SITE_URL}{path}")
+    }
+}
+
+/// Title, description and link preview (Open Graph and Twitter card) metadata of
+/// a page. Every route should render exactly one of these.
+#[component]
+pub fn PageMeta(
+    #[prop(into)] title: String,
+    #[prop(into)] description: String,
+    /// Path of the page, which its canonical URL is built from.
+    #[prop(into)]
+    path: String,
+    /// Site relative or absolute URL of the preview image.
+    #[prop(optional, into)]
+    image: Option<String>,
+    /// Open Graph type of the page, such as `website` or `article`.
+    #[prop(default = "website")]
+    og_type: &'static str,
+) -> impl IntoView {
+    let url = absolute_url(&path);
+    let twitter_card = if image.is_some() {
+        "summary_large_image"
+    } else {
+        "summary"
+    };
+    let image = absolute_url(image.as_deref().unwrap_or(DEFAULT_IMAGE));
+
+    view! {
+        <Title text=title.clone()/>
+        <Meta name="description" content=description.clone()/>
+        <Link rel="canonical" href=url.clone()/>
+
+        <Meta property="og:site_name" content="CodeBoi"/>
+        <Meta property="og:type" content=og_type/>
+        <Meta property="og:title" content=title.clone()/>
+        <Meta property="og:description" content=description.clone()/>
+        <Meta property="og:url" content=url/>
+        <Meta property="og:image" content=image.clone()/>
+
+        <Meta name="twitter:card" content=twitter_card/>
+        <Meta name="twitter:title" content=title/>
+        <Meta name="twitter:description" content=description/>
+        <Meta name="twitter:image" content=image/>
+    }
+}
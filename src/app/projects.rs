@@ -1,26 +1,135 @@
 use icondata as i;
 use leptos::*;
 use leptos_icons::*;
-use leptos_meta::*;
+use leptos_router::*;
+use leptos_use::use_intersection_observer;
 use serde::{Deserialize, Serialize};
 use stylers::style;
 use time::Date;
 
+use crate::app::page_meta::PageMeta;
+use crate::app::structured_data::{JsonLd, SoftwareSourceCode};
+use crate::error_template::{AppError, ErrorTemplate};
+
+#[derive(Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Technology {
+    pub id: i32,
+    pub slug: String,
+    pub name: String,
+    /// Key of the icon shown for this technology, see [`icon_from_key`].
+    pub icon: Option<String>,
+    pub homepage_url: Option<String>,
+}
+
+/// One processed size and format of a project's uploaded image.
+#[derive(Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct ImageVariant {
+    pub width: i32,
+    pub height: i32,
+    /// File extension of the variant: `png`, `webp` or `avif`.
+    pub format: String,
+    /// Absolute path the variant is served at.
+    pub path: String,
+}
+
 #[derive(Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
-#[cfg_attr(feature = "ssr", derive(sqlx::FromRow))]
 pub struct Project {
     pub id: i32,
+    pub slug: String,
     pub name: String,
     pub short_description: String,
+    /// Markdown source of the project's write-up.
     pub long_description: String,
+    /// `long_description` rendered to sanitized HTML on the server.
+    pub long_description_html: String,
     pub url: Option<String>,
-    pub technologies: Option<String>,
+    pub technologies: Vec<Technology>,
+    pub images: Vec<ImageVariant>,
+    /// Tiny data URI version of the image, shown blurred while it loads.
+    pub image_placeholder: Option<String>,
     pub pinned: bool,
     pub date_created: Date,
 }
 
-fn tech_str_to_icon(tech_str: &str) -> Option<i::Icon> {
-    match tech_str {
+/// Number of projects per page of [`search_projects`].
+pub const PROJECTS_PAGE_SIZE: usize = 10;
+
+/// Position in the projects list, which is ordered by `date_created` and then
+/// `id`, both descending. Pages continue right after the cursor's project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProjectCursor {
+    pub date_created: Date,
+    pub id: i32,
+}
+
+impl ProjectCursor {
+    pub fn after(project: &Project) -> Self {
+        Self {
+            date_created: project.date_created,
+            id: project.id,
+        }
+    }
+
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let (julian_day, id) = cursor.split_once('.')?;
+        Some(Self {
+            date_created: Date::from_julian_day(julian_day.parse().ok()?).ok()?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for ProjectCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.date_created.to_julian_day(), self.id)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectPage {
+    pub projects: Vec<Project>,
+    /// Cursor to pass back for the following page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+impl Project {
+    /// Changes whenever anything shown on the project's preview card does.
+    pub fn content_hash(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.name.hash(&mut hasher);
+        self.short_description.hash(&mut hasher);
+        for technology in &self.technologies {
+            technology.icon.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Path of the preview card rendered for link previews, which changes along
+    /// with the card so stale copies aren't served from caches.
+    pub fn og_image_path(&self) -> String {
+        format!("/og/projects/{}.png?v={:x}", self.slug, self.content_hash())
+    }
+
+    /// Where a screenshot of the project was put by hand, before images could be
+    /// uploaded.
+    pub fn legacy_image_path(&self) -> String {
+        format!("/images/projects/{}.png", self.slug)
+    }
+
+    /// The largest PNG variant of the project's image, which every browser and
+    /// link preview can show.
+    pub fn fallback_image(&self) -> Option<&ImageVariant> {
+        self.images
+            .iter()
+            .filter(|image| image.format == "png")
+            .max_by_key(|image| image.width)
+    }
+}
+
+pub(crate) fn icon_from_key(key: &str) -> Option<i::Icon> {
+    match key {
         "python" => Some(i::SiPython),
         "rust" => Some(i::SiRust),
         "flask" => Some(i::SiFlask),
@@ -42,57 +151,195 @@ fn tech_str_to_icon(tech_str: &str) -> Option<i::Icon> {
     }
 }
 
-fn capitalize_str(input: &str) -> String {
-    input
-        .chars()
-        .enumerate()
-        .map(|(i, ch)| {
-            if i == 0 {
-                ch.to_ascii_uppercase()
-            } else {
-                ch.to_ascii_lowercase()
-            }
-        })
-        .collect()
+#[server(GetProjects, "/api", "GetJson")]
+pub async fn get_projects(cursor: Option<String>) -> Result<ProjectPage, ServerFnError> {
+    search_projects(None, None, cursor).await
 }
 
-#[server(GetProjects)]
-pub async fn get_projects() -> Result<Vec<Project>, ServerFnError> {
-    use sqlx::PgPool;
-    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+#[server(GetPinnedProjects, "/api", "GetJson")]
+pub async fn get_pinned_projects() -> Result<Vec<Project>, ServerFnError> {
+    use crate::store::SharedProjectStore;
+    let store = use_context::<SharedProjectStore>()
+        .ok_or(ServerFnError::new("State `ProjectStore` not found."))?;
 
-    sqlx::query_as!(Project, "SELECT * FROM projects ORDER BY date_created DESC")
-        .fetch_all(&pool)
+    store
+        .pinned_projects()
         .await
         .map_err(|e| ServerFnError::new(e))
 }
 
-#[server(GetPinnedProjects)]
-pub async fn get_pinned_projects() -> Result<Vec<Project>, ServerFnError> {
-    use sqlx::PgPool;
-    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
-
-    sqlx::query_as!(
-        Project,
-        "SELECT * FROM projects WHERE pinned = true ORDER BY date_created DESC"
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e))
+/// Filters projects by technology slug and full-text search, one page at a time.
+/// Blank filters are ignored.
+#[server(SearchProjects, "/api", "GetJson")]
+pub async fn search_projects(
+    tech: Option<String>,
+    q: Option<String>,
+    cursor: Option<String>,
+) -> Result<ProjectPage, ServerFnError> {
+    use crate::store::SharedProjectStore;
+    let store = use_context::<SharedProjectStore>()
+        .ok_or(ServerFnError::new("State `ProjectStore` not found."))?;
+
+    let tech = tech.as_deref().map(str::trim).filter(|tech| !tech.is_empty());
+    let q = q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let after = match cursor {
+        Some(cursor) => {
+            Some(ProjectCursor::parse(&cursor).ok_or(ServerFnError::new("Invalid cursor."))?)
+        }
+        None => None,
+    };
+
+    // Fetch one extra project to find out whether there is another page
+    let mut projects = store
+        .search_projects(tech, q, after, PROJECTS_PAGE_SIZE + 1)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+
+    let next_cursor = if projects.len() > PROJECTS_PAGE_SIZE {
+        projects.truncate(PROJECTS_PAGE_SIZE);
+        projects
+            .last()
+            .map(|project| ProjectCursor::after(project).to_string())
+    } else {
+        None
+    };
+
+    Ok(ProjectPage {
+        projects,
+        next_cursor,
+    })
+}
+
+#[server(GetTechnologies, "/api", "GetJson")]
+pub async fn get_technologies() -> Result<Vec<Technology>, ServerFnError> {
+    use crate::store::SharedProjectStore;
+    let store = use_context::<SharedProjectStore>()
+        .ok_or(ServerFnError::new("State `ProjectStore` not found."))?;
+
+    store
+        .technologies()
+        .await
+        .map_err(|e| ServerFnError::new(e))
+}
+
+/// Looks up a project by its slug, falling back to its numeric id so that links
+/// shared before slugs existed can still be resolved (and redirected).
+#[server(GetProject, "/api", "GetJson")]
+pub async fn get_project(slug: String) -> Result<Option<Project>, ServerFnError> {
+    use crate::store::SharedProjectStore;
+    let store = use_context::<SharedProjectStore>()
+        .ok_or(ServerFnError::new("State `ProjectStore` not found."))?;
+
+    let project = store
+        .project_by_slug(&slug)
+        .await
+        .map_err(|e| ServerFnError::new(e))?;
+
+    match (project, slug.parse::<i32>()) {
+        (None, Ok(id)) => store
+            .project_by_id(id)
+            .await
+            .map_err(|e| ServerFnError::new(e)),
+        (project, _) => Ok(project),
+    }
 }
 
 #[component]
 pub fn Projects() -> impl IntoView {
-    let projects = create_resource(|| (), |_| get_projects());
+    let query = use_query_map();
+    let tech_filter = move || query.with(|q| q.get("tech").cloned().unwrap_or_default());
+    let text_filter = move || query.with(|q| q.get("q").cloned().unwrap_or_default());
+
+    let projects = create_resource(
+        move || (tech_filter(), text_filter()),
+        |(tech, q)| search_projects(Some(tech), Some(q), None),
+    );
+    let technologies = create_resource(|| (), |_| get_technologies());
+
+    let style_class = style! {
+        .filter-fields {
+            display: flex;
+            flex-wrap: wrap;
+            gap: 8px;
+            margin: 16px 0px;
+        }
+
+        .filter-fields input, .filter-fields select, .filter-fields button {
+            font-family: inherit;
+            font-size: 1rem;
+            color: var(--ghost-white);
+            background-color: var(--gunmetal);
+            border: 2px solid rgba(255, 255, 255, 0.24);
+            border-radius: 8px;
+            padding: 4px 8px;
+        }
 
-    view! {
-        <Title text="CodeBoi's Projects"/>
+        .filter-fields input {
+            flex-grow: 1;
+        }
+
+        .filter-fields button {
+            cursor: pointer;
+        }
+    };
+
+    view! { class=style_class,
+        <PageMeta
+            title="CodeBoi's Projects"
+            description="Everything CodeBoi has built, searchable by technology."
+            path="/projects"
+        />
 
         <a href="/" class="muted">
             "< Back"
         </a>
 
         <h2 align="center">Projects</h2>
+        <div class="filter-bar">
+            <Form method="get" action="/projects">
+                <div class="filter-fields">
+                    <input
+                        type="search"
+                        name="q"
+                        placeholder="Search projects..."
+                        value=text_filter
+                    />
+                    <Transition fallback=move || view! { class=style_class, <select name="tech"></select> }>
+                        {move || {
+                            let technologies = technologies
+                                .get()
+                                .and_then(Result::ok)
+                                .unwrap_or_default();
+
+                            view! { class=style_class,
+                                <select name="tech">
+                                    <option value="">"All technologies"</option>
+                                    {technologies
+                                        .into_iter()
+                                        .map(|tech| {
+                                            let selected = tech.slug == tech_filter();
+                                            view! { class=style_class,
+                                                <option value=tech.slug selected=selected>
+                                                    {tech.name}
+                                                </option>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </select>
+                            }
+                        }}
+
+                    </Transition>
+                    <button type="submit">"Filter"</button>
+                </div>
+            </Form>
+            <Show when=move || !tech_filter().is_empty() || !text_filter().is_empty()>
+                <a href="/projects" class="muted">
+                    "Clear filters"
+                </a>
+            </Show>
+        </div>
+
         <div class="projects">
             <Transition fallback=move || view! { <p>"Loading..."</p> }>
                 <ErrorBoundary fallback=move |_| {
@@ -103,15 +350,13 @@ pub fn Projects() -> impl IntoView {
                             .get()
                             .map(|projects| {
                                 match projects {
-                                    Ok(projects) => {
+                                    Ok(page) if page.projects.is_empty() => {
+                                        view! { <p align="center">"No projects match those filters."</p> }
+                                            .into_view()
+                                    }
+                                    Ok(page) => {
                                         view! {
-                                            <For
-                                                each=move || projects.clone()
-                                                key=|p| p.id
-                                                children=move |project| {
-                                                    view! { <ProjectCard project=&project/> }
-                                                }
-                                            />
+                                            <ProjectList page tech=tech_filter() q=text_filter()/>
                                         }
                                     }
                                     Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
@@ -125,6 +370,81 @@ pub fn Projects() -> impl IntoView {
     }
 }
 
+/// The first `page` of projects followed by the pages after it, which are
+/// loaded as the end of the list scrolls into view.
+#[component]
+fn ProjectList(page: ProjectPage, tech: String, q: String) -> impl IntoView {
+    let projects = create_rw_signal(page.projects);
+    let next_cursor = create_rw_signal(page.next_cursor);
+
+    let load_more = create_action(move |cursor: &String| {
+        let (tech, q, cursor) = (tech.clone(), q.clone(), cursor.clone());
+        async move {
+            let page = search_projects(Some(tech), Some(q), Some(cursor)).await?;
+            projects.update(|projects| projects.extend(page.projects));
+            next_cursor.set(page.next_cursor);
+            Ok::<_, ServerFnError>(())
+        }
+    });
+    let load_next_page = move || {
+        if let Some(cursor) = next_cursor.get_untracked() {
+            if !load_more.pending().get_untracked() {
+                load_more.dispatch(cursor);
+            }
+        }
+    };
+
+    let list_end = create_node_ref::<html::Div>();
+    use_intersection_observer(list_end, move |entries, _| {
+        if entries.iter().any(|entry| entry.is_intersecting()) {
+            load_next_page();
+        }
+    });
+
+    let style_class = style! {
+        .load-more {
+            font-family: inherit;
+            font-size: 1rem;
+            color: var(--ghost-white);
+            background-color: var(--gunmetal);
+            border: 2px solid rgba(255, 255, 255, 0.24);
+            border-radius: 8px;
+            padding: 4px 12px;
+            cursor: pointer;
+        }
+    };
+
+    view! { class=style_class,
+        <For
+            each=move || projects.get()
+            key=|p| p.id
+            children=move |project| {
+                view! { <ProjectCard project=&project/> }
+            }
+        />
+
+        {move || {
+            load_more
+                .value()
+                .get()
+                .and_then(Result::err)
+                .map(|e| view! { <p align="center">{e.to_string()}</p> })
+        }}
+
+        <Show when=move || next_cursor.with(Option::is_some)>
+            <div node_ref=list_end align="center">
+                <button
+                    class="load-more"
+                    on:click=move |_| load_next_page()
+                    disabled=move || load_more.pending().get()
+                >
+                    {move || if load_more.pending().get() { "Loading..." } else { "Load more" }}
+                </button>
+            </div>
+        </Show>
+    }
+}
+
 #[component]
 pub fn PinnedProjectCard<'a>(project: &'a Project) -> impl IntoView {
     let style_class = style! {
@@ -136,9 +456,9 @@ pub fn PinnedProjectCard<'a>(project: &'a Project) -> impl IntoView {
             gap: 10px;
         }
 
-        .pinned-project img {
-            max-width: 100%;
-            height: auto;
+        .pinned-img {
+            width: 100%;
+            overflow: hidden;
             box-shadow: 0px 0px 8px -1px black;
             border-radius: 8px;
             margin: 8px auto;
@@ -154,8 +474,7 @@ pub fn PinnedProjectCard<'a>(project: &'a Project) -> impl IntoView {
                 grid-template-rows: 1fr max-content;
             }
 
-            .pinned-project img {
-                width: auto;
+            .pinned-img {
                 height: 12rem;
             }
         }
@@ -163,22 +482,13 @@ pub fn PinnedProjectCard<'a>(project: &'a Project) -> impl IntoView {
 
     view! { class=style_class,
         <div class="pinned-project content content-border">
-            <img src=format!("/images/projects/{}.png", project.id)/>
+            <div class="pinned-img">
+                <ProjectImage project sizes="(max-width: 600px) 90vw, 400px"/>
+            </div>
 
             <div class="info">
                 <h2>
-                    // TODO: Change this to link to project card or detail
-                    {if let Some(ref url) = project.url {
-                        view! {
-                            <a href=url target="_blank">
-                                {&project.name}
-                            </a>
-                        }
-                            .into_view()
-                    } else {
-                        view! { <span>{&project.name}</span> }.into_view()
-                    }}
-
+                    <a href=format!("/projects/{}", project.slug)>{&project.name}</a>
                 </h2>
                 <p>{&project.short_description}</p>
             </div>
@@ -197,16 +507,141 @@ pub fn ProjectCard<'a>(project: &'a Project) -> impl IntoView {
         }
 
         .project-img {
-            width: 100%;
-            object-fit: cover;
             max-height: "25em";
-            border-radius: 12px 12px 0px 0px;
+            overflow: hidden;
+            border-radius: 9px 9px 0px 0px;
         }
 
         .project-info {
             padding: 0px 25px 12px 25px;
         }
+    };
+
+    view! { class=style_class,
+        <div class="project-card content-border" id=project.slug.clone()>
+            <div class="project-img">
+                <ProjectImage project sizes="(max-width: 1000px) 80vw, 800px"/>
+            </div>
+
+            <div class="project-info">
+                <h2>
+                    <a href=format!("/projects/{}", project.slug)>{&project.name}</a>
+                </h2>
+
+                <div class="markdown" inner_html=project.long_description_html.clone()></div>
+
+                {(!project.technologies.is_empty())
+                    .then(|| view! { <TechStack technologies=&project.technologies/> })}
+
+            </div>
+        </div>
+    }
+}
+
+/// Responsive picture of a project built from its image variants, which fades
+/// in over its blurry placeholder once loaded. Projects without uploaded images
+/// show their hand-placed screenshot instead.
+#[component]
+pub fn ProjectImage<'a>(project: &'a Project, sizes: &'static str) -> impl IntoView {
+    let style_class = style! {
+        .blur-up {
+            position: relative;
+            height: 100%;
+            overflow: hidden;
+        }
+
+        .placeholder {
+            position: absolute;
+            inset: 0;
+            background-size: cover;
+            background-position: center;
+            filter: blur(12px);
+            // Hides the edges the blur fades out
+            transform: scale(1.1);
+        }
+
+        picture, img {
+            position: relative;
+            display: block;
+            width: 100%;
+            height: 100%;
+            object-fit: cover;
+        }
+
+        img {
+            transition: opacity 0.4s ease-in;
+        }
+
+        img.loading {
+            opacity: 0;
+        }
+    };
+
+    // Images are only hidden while loading once hydrated, as without JS nothing
+    // would ever reveal them
+    let (hydrated, set_hydrated) = create_signal(false);
+    create_effect(move |_| set_hydrated(true));
+    let (loaded, set_loaded) = create_signal(false);
+    let image_ref = create_node_ref::<html::Img>();
+    // Cached images can finish loading before hydration attaches `on:load`
+    image_ref.on_load(move |image| {
+        if image.complete() {
+            set_loaded(true);
+        }
+    });
+
+    let srcset = |format: &str| {
+        project
+            .images
+            .iter()
+            .filter(|image| image.format == format)
+            .map(|image| format!("{} {}w", image.path, image.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let placeholder_style = project
+        .image_placeholder
+        .as_ref()
+        .map(|placeholder| format!("background-image: url({placeholder})"));
+
+    let Some(fallback) = project.fallback_image() else {
+        return view! { class=style_class,
+            <img
+                src=project.legacy_image_path()
+                alt=format!("Screenshot of {}", project.name)
+                loading="lazy"
+            />
+        }
+        .into_view();
+    };
 
+    view! { class=style_class,
+        <div class="blur-up">
+            <div class="placeholder" style=placeholder_style></div>
+            <picture>
+                <source type="image/avif" srcset=srcset("avif") sizes=sizes/>
+                <source type="image/webp" srcset=srcset("webp") sizes=sizes/>
+                <img
+                    node_ref=image_ref
+                    class:loading=move || hydrated() && !loaded()
+                    on:load=move |_| set_loaded(true)
+                    src=fallback.path.clone()
+                    srcset=srcset("png")
+                    sizes=sizes
+                    width=fallback.width
+                    height=fallback.height
+                    alt=format!("Screenshot of {}", project.name)
+                    loading="lazy"
+                />
+            </picture>
+        </div>
+    }
+    .into_view()
+}
+
+#[component]
+pub fn TechStack<'a>(technologies: &'a [Technology]) -> impl IntoView {
+    let style_class = style! {
         .tech-stack {
             display: flex;
             gap: "0.5em";
@@ -216,57 +651,186 @@ pub fn ProjectCard<'a>(project: &'a Project) -> impl IntoView {
             width: min-content;
             height: min-content;
         }
+
+        .tech-icon a {
+            color: inherit;
+        }
     };
 
+    let tech_icons = technologies
+        .iter()
+        .map(|tech| {
+            let icon = match tech.icon.as_deref().and_then(icon_from_key) {
+                Some(icon) => view! { <Icon icon width="2em" height="auto"/> }.into_view(),
+                None => view! { <span>{&tech.name}</span> }.into_view(),
+            };
+
+            let icon = match tech.homepage_url {
+                Some(ref url) => view! { class=style_class,
+                    <a href=url target="_blank">
+                        {icon}
+                    </a>
+                }
+                    .into_view(),
+                None => icon,
+            };
+
+            view! { class=style_class,
+                <span class="tech-icon" title=&tech.name>
+                    {icon}
+                </span>
+            }
+        })
+        .collect_view();
+
     view! { class=style_class,
-        <div class="project-card content-border" id=project.id>
-            // TODO: Image blur effect
-            <img src=format!("/images/projects/{}.png", project.id) class="project-img"/>
+        <br/>
+        <h3>Tech Stack</h3>
+        <p class="tech-stack">{tech_icons}</p>
+    }
+}
 
-            <div class="project-info">
-                <h2>
-                    {if let Some(ref url) = project.url {
-                        view! {
-                            <a href=url target="_blank">
-                                {&project.name}
-                            </a>
-                        }
-                            .into_view()
-                    } else {
-                        view! { <span>{&project.name}</span> }.into_view()
-                    }}
+#[component]
+pub fn ProjectDetail() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.with(|p| p.get("slug").cloned().unwrap_or_default());
+    let project = create_resource(slug, get_project);
 
-                </h2>
+    let style_class = style! {
+        .project-detail {
+            margin: 16px 0px;
+            padding: 0px;
+        }
 
-                <p>{&project.long_description}</p>
+        .project-img {
+            max-height: "25em";
+            overflow: hidden;
+            border-radius: 9px 9px 0px 0px;
+        }
 
-                {if let Some(ref technologies) = project.technologies {
-                    let tech_icons = technologies
-                        .split(",")
-                        .filter_map(|tech_name| {
-                            let trimmed_name = tech_name.trim();
-                            tech_str_to_icon(trimmed_name)
-                                .map(|icon| (capitalize_str(trimmed_name), icon))
-                        })
-                        .map(|(icon_name, icon)| {
-                            view! { class=style_class,
-                                <span class="tech-icon" title=icon_name>
-                                    <Icon icon width="2em" height="auto"/>
-                                </span>
+        .project-info {
+            padding: 0px 25px 12px 25px;
+        }
+    };
+
+    view! { class=style_class,
+        <a href="/projects" class="muted">
+            "< Back"
+        </a>
+
+        <Transition fallback=move || view! { <p>"Loading..."</p> }>
+            <ErrorBoundary fallback=|_| {
+                view! { <p>"oops"</p> }
+            }>
+                {move || {
+                    project
+                        .get()
+                        .map(|project| {
+                            match project {
+                                Ok(Some(project)) if project.slug != slug() => {
+                                    let redirect = view! {
+                                        <Redirect
+                                            path=format!("/projects/{}", project.slug)
+                                            options=NavigateOptions {
+                                                replace: true,
+                                                ..Default::default()
+                                            }
+                                        />
+                                    };
+
+                                    // `Redirect` responds with a 302, but old id based links are
+                                    // never coming back, so tell crawlers to update them
+                                    #[cfg(feature = "ssr")]
+                                    if let Some(response) = use_context::<leptos_axum::ResponseOptions>() {
+                                        response.set_status(http::StatusCode::MOVED_PERMANENTLY);
+                                    }
+
+                                    redirect.into_view()
+                                }
+                                Ok(Some(project)) => {
+                                    view! { class=style_class,
+                                        <PageMeta
+                                            title=format!("{} | CodeBoi's Projects", project.name)
+                                            description=project.short_description.clone()
+                                            path=format!("/projects/{}", project.slug)
+                                            image=project.og_image_path()
+                                            og_type="article"
+                                        />
+                                        <JsonLd data=SoftwareSourceCode::from(&project)/>
+
+                                        <h2 align="center">{&project.name}</h2>
+                                        <div class="project-detail content content-border">
+                                            <div class="project-img">
+                                                <ProjectImage
+                                                    project=&project
+                                                    sizes="(max-width: 1000px) 80vw, 800px"
+                                                />
+                                            </div>
+
+                                            <div class="project-info">
+                                                <div class="markdown" inner_html=project.long_description_html.clone()></div>
+
+                                                {(!project.technologies.is_empty())
+                                                    .then(|| {
+                                                        view! { <TechStack technologies=&project.technologies/> }
+                                                    })}
+
+                                                {project
+                                                    .url
+                                                    .as_ref()
+                                                    .map(|url| {
+                                                        view! { class=style_class,
+                                                            <br/>
+                                                            <a href=url target="_blank">
+                                                                "Check it out!"
+                                                            </a>
+                                                        }
+                                                    })}
+
+                                            </div>
+                                        </div>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(None) => {
+                                    let mut outside_errors = Errors::default();
+                                    outside_errors.insert_with_default_key(AppError::NotFound);
+                                    view! { <ErrorTemplate outside_errors/> }.into_view()
+                                }
+                                Err(e) => view! { <p>{e.to_string()}</p> }.into_view(),
                             }
                         })
-                        .collect_view();
-                    view! { class=style_class,
-                        <br/>
-                        <h3>Tech Stack</h3>
-                        <p class="tech-stack">{tech_icons}</p>
-                    }
-                        .into_view()
-                } else {
-                    view! {}.into_view()
                 }}
 
-            </div>
-        </div>
+            </ErrorBoundary>
+        </Transition>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = ProjectCursor {
+            date_created: date!(2023 - 09 - 13),
+            id: 42,
+        };
+        assert_eq!(ProjectCursor::parse(&cursor.to_string()), Some(cursor));
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        for cursor in ["", "2460201", "2460201.", ".42", "abc.42", "2460201.abc", "1.2.3"] {
+            assert_eq!(ProjectCursor::parse(cursor), None, "{cursor:?}");
+        }
+    }
+
+    #[test]
+    fn cursor_rejects_out_of_range_dates() {
+        assert_eq!(ProjectCursor::parse(&format!("{}.1", i32::MAX)), None);
     }
 }
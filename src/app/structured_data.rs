@@ -0,0 +1,92 @@
+use leptos::*;
+use serde::Serialize;
+
+use crate::app::projects::Project;
+use crate::app::SITE_URL;
+
+/// schema.org description of the site's owner.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    pub name: &'static str,
+    pub alternate_name: &'static str,
+    pub url: &'static str,
+    pub image: String,
+    pub email: &'static str,
+    /// Profiles of the person on other sites.
+    pub same_as: Vec<&'static str>,
+}
+
+/// schema.org description of a project.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoftwareSourceCode {
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_repository: Option<String>,
+    pub image: String,
+    pub date_created: String,
+    pub keywords: Vec<String>,
+    pub author: Author,
+}
+
+/// Reference to a [`Person`] by name, to avoid repeating all of their details.
+#[derive(Clone, Serialize)]
+pub struct Author {
+    #[serde(rename = "@type")]
+    pub kind: &'static str,
+    pub name: &'static str,
+}
+
+pub const OWNER_NAME: &str = "Rehatbir Singh";
+
+impl From<&Project> for SoftwareSourceCode {
+    fn from(project: &Project) -> Self {
+        Self {
+            kind: "SoftwareSourceCode",
+            name: project.name.clone(),
+            description: project.short_description.clone(),
+            url: format!("{SITE_URL}/projects/{}", project.slug),
+            code_repository: project.url.clone(),
+            image: format!("{SITE_URL}{}", project.og_image_path()),
+            date_created: project.date_created.to_string(),
+            keywords: project
+                .technologies
+                .iter()
+                .map(|technology| technology.name.clone())
+                .collect(),
+            author: Author {
+                kind: "Person",
+                name: OWNER_NAME,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WithContext<'a, T> {
+    #[serde(rename = "@context")]
+    context: &'static str,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+/// Embeds `data` in the page as a JSON-LD script for search engines.
+#[component]
+pub fn JsonLd<T: Serialize + 'static>(data: T) -> impl IntoView {
+    let json = serde_json::to_string(&WithContext {
+        context: "https://schema.org",
+        data: &data,
+    })
+    .unwrap_or_default()
+    // Keep the JSON from closing the script element early
+    .replace("</", "<\\/");
+
+    view! { <script type="application/ld+json" inner_html=json></script> }
+}
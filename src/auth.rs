@@ -0,0 +1,204 @@
+use std::sync::OnceLock;
+
+use argon2::{
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+    },
+    Argon2,
+};
+use http::{header, request::Parts, HeaderMap, HeaderValue};
+use leptos::*;
+use leptos_axum::ResponseOptions;
+use sqlx::PgPool;
+
+pub const SESSION_COOKIE: &str = "admin_session";
+const SESSION_DAYS: i32 = 30;
+
+#[derive(Clone, Debug)]
+pub struct AdminUser {
+    pub id: i32,
+    pub username: String,
+}
+
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// A hash of no one's password, checked against when the username is unknown
+/// so that a login takes as long whether or not the user exists.
+fn dummy_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let mut password = [0u8; 32];
+        OsRng.fill_bytes(&mut password);
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(&password, &salt)
+            .expect("hashing with default parameters succeeds")
+            .to_string()
+    })
+}
+
+/// Returns the admin with the given credentials, if there is one.
+pub async fn authenticate(
+    pool: &PgPool,
+    username: &str,
+    password: &str,
+) -> Result<Option<AdminUser>, sqlx::Error> {
+    let user = sqlx::query!(
+        "SELECT id, username, password_hash FROM admin_users WHERE username = $1",
+        username
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(user) = user else {
+        if let Ok(hash) = PasswordHash::new(dummy_password_hash()) {
+            let _ = Argon2::default().verify_password(password.as_bytes(), &hash);
+        }
+        return Ok(None);
+    };
+
+    let verified = PasswordHash::new(&user.password_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    });
+
+    Ok(verified.then(|| AdminUser {
+        id: user.id,
+        username: user.username,
+    }))
+}
+
+/// Creates the admin, or resets their password if they already exist.
+pub async fn upsert_admin_user(
+    pool: &PgPool,
+    username: &str,
+    password: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let password_hash = hash_password(password)?;
+
+    sqlx::query!(
+        "INSERT INTO admin_users (username, password_hash) VALUES ($1, $2)
+        ON CONFLICT (username) DO UPDATE SET password_hash = EXCLUDED.password_hash",
+        username,
+        password_hash
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Starts a new session for the admin and returns its token.
+pub async fn create_session(pool: &PgPool, admin_user_id: i32) -> Result<String, sqlx::Error> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+
+    sqlx::query!(
+        "INSERT INTO admin_sessions (token, admin_user_id, expires_at)
+        VALUES ($1, $2, NOW() + make_interval(days => $3))",
+        token,
+        admin_user_id,
+        SESSION_DAYS
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(token)
+}
+
+pub async fn delete_session(pool: &PgPool, token: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM admin_sessions WHERE token = $1", token)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// The session token sent with the current request, if any.
+pub fn session_token() -> Option<String> {
+    session_token_from(&use_context::<Parts>()?.headers)
+}
+
+/// The session token sent in the `Cookie` headers, if any.
+pub fn session_token_from(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token.to_owned())
+}
+
+/// The admin logged in for the current request, if any.
+pub async fn current_admin(pool: &PgPool) -> Result<Option<AdminUser>, sqlx::Error> {
+    match session_token() {
+        Some(token) => admin_for_token(pool, &token).await,
+        None => Ok(None),
+    }
+}
+
+/// The admin the unexpired session `token` belongs to, if any.
+pub async fn admin_for_token(pool: &PgPool, token: &str) -> Result<Option<AdminUser>, sqlx::Error> {
+    sqlx::query_as!(
+        AdminUser,
+        "SELECT u.id, u.username
+        FROM admin_sessions s
+        JOIN admin_users u ON u.id = s.admin_user_id
+        WHERE s.token = $1 AND s.expires_at > NOW()",
+        token
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Rejects the current server function call unless it comes from a logged in admin.
+pub async fn require_admin() -> Result<AdminUser, ServerFnError> {
+    let pool = use_context::<PgPool>().ok_or(ServerFnError::new("State `PgPool` not found."))?;
+
+    current_admin(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(e))?
+        .ok_or(ServerFnError::new("Not logged in."))
+}
+
+pub fn set_session_cookie(token: &str) {
+    let max_age = SESSION_DAYS * 24 * 60 * 60;
+    append_cookie(format!(
+        "{SESSION_COOKIE}={token}; Path=/; Max-Age={max_age}; HttpOnly; SameSite=Lax{}",
+        secure_attribute()
+    ));
+}
+
+pub fn clear_session_cookie() {
+    append_cookie(format!(
+        "{SESSION_COOKIE}=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax{}",
+        secure_attribute()
+    ));
+}
+
+fn append_cookie(cookie: String) {
+    if let (Some(response), Ok(cookie)) = (
+        use_context::<ResponseOptions>(),
+        HeaderValue::from_str(&cookie),
+    ) {
+        response.append_header(header::SET_COOKIE, cookie);
+    }
+}
+
+fn secure_attribute() -> &'static str {
+    // Local development is usually served over plain http
+    if cfg!(debug_assertions) {
+        ""
+    } else {
+        "; Secure"
+    }
+}
@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    future::Future,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use sqlx::{postgres::PgListener, PgPool};
+
+use crate::app::projects::{Project, ProjectCursor, Technology};
+use crate::store::{ProjectStore, SharedProjectStore, StoreError};
+
+/// Channel the `projects_changed` trigger notifies on.
+const CHANGES_CHANNEL: &str = "projects_changed";
+/// How long to wait before listening again after the connection fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Most entries each cache holds before the least recently used is evicted.
+/// Keys come from query strings, so without a cap they could grow forever.
+const MAX_ENTRIES: usize = 256;
+
+/// Counts of how well the project cache is doing, served at `/metrics` by
+/// [`metrics_routes`].
+#[derive(Default)]
+pub struct CacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum ListKey {
+    All,
+    Pinned,
+    /// Searches without a free-text query, which are few enough to be worth
+    /// caching.
+    Search {
+        tech: Option<String>,
+        after: Option<ProjectCursor>,
+        limit: usize,
+    },
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum ProjectKey {
+    Slug(String),
+    Id(i32),
+}
+
+struct Entry<V> {
+    expires: Instant,
+    /// When the entry was last read or written, for LRU eviction.
+    used: u64,
+    value: V,
+}
+
+struct Entries<K, V> {
+    map: HashMap<K, Entry<V>>,
+    /// Incremented on every access; a logical clock for `Entry::used`.
+    clock: u64,
+}
+
+/// Up to [`MAX_ENTRIES`] values that expire after a while, or when the cache is
+/// invalidated.
+struct Cache<K, V> {
+    entries: Mutex<Entries<K, V>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Cache<K, V> {
+    fn new() -> Self {
+        Self {
+            entries: Mutex::new(Entries {
+                map: HashMap::new(),
+                clock: 0,
+            }),
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clock += 1;
+        let clock = entries.clock;
+        let entry = entries
+            .map
+            .get_mut(key)
+            .filter(|entry| entry.expires > Instant::now())?;
+        entry.used = clock;
+        Some(entry.value.clone())
+    }
+
+    /// Stores `value` unless the cache was invalidated since it was fetched, in
+    /// which case it may already be stale.
+    ///
+    /// Expired entries are dropped first, and if the cache is still full the
+    /// least recently used entry makes room.
+    fn insert(&self, key: K, value: V, expires: Instant, generation: &AtomicU64, fetched_at: u64) {
+        let mut entries = self.entries.lock().unwrap();
+        if generation.load(Ordering::SeqCst) != fetched_at {
+            return;
+        }
+
+        let now = Instant::now();
+        entries.map.retain(|_, entry| entry.expires > now);
+        if entries.map.len() >= MAX_ENTRIES && !entries.map.contains_key(&key) {
+            let oldest = entries
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.map.remove(&oldest);
+            }
+        }
+
+        entries.clock += 1;
+        let used = entries.clock;
+        entries.map.insert(
+            key,
+            Entry {
+                expires,
+                used,
+                value,
+            },
+        );
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().map.clear();
+    }
+}
+
+/// Keeps the results of another store's queries for `ttl`, or until
+/// [`ProjectStore::invalidate`] is called.
+pub struct CachedProjectStore {
+    inner: SharedProjectStore,
+    ttl: Duration,
+    /// Bumped by every invalidation.
+    generation: AtomicU64,
+    lists: Cache<ListKey, Vec<Project>>,
+    projects: Cache<ProjectKey, Option<Project>>,
+    technologies: Cache<(), Vec<Technology>>,
+    metrics: Arc<CacheMetrics>,
+}
+
+impl CachedProjectStore {
+    pub fn new(inner: SharedProjectStore, ttl: Duration, metrics: Arc<CacheMetrics>) -> Self {
+        Self {
+            inner,
+            ttl,
+            generation: AtomicU64::new(0),
+            lists: Cache::new(),
+            projects: Cache::new(),
+            technologies: Cache::new(),
+            metrics,
+        }
+    }
+
+    /// Answers from `cache`, or runs `fetch` and caches its result if `keep`
+    /// says it is worth it.
+    async fn cached<K, V>(
+        &self,
+        cache: &Cache<K, V>,
+        key: K,
+        fetch: impl Future<Output = Result<V, StoreError>>,
+        keep: impl FnOnce(&V) -> bool,
+    ) -> Result<V, StoreError>
+    where
+        K: Eq + Hash + Clone,
+        V: Clone,
+    {
+        if let Some(value) = cache.get(&key) {
+            self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+        self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+
+        let fetched_at = self.generation.load(Ordering::SeqCst);
+        let value = fetch.await?;
+        if !keep(&value) {
+            return Ok(value);
+        }
+        cache.insert(
+            key,
+            value.clone(),
+            Instant::now() + self.ttl,
+            &self.generation,
+            fetched_at,
+        );
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl ProjectStore for CachedProjectStore {
+    async fn projects(&self) -> Result<Vec<Project>, StoreError> {
+        self.cached(&self.lists, ListKey::All, self.inner.projects(), |_| true)
+            .await
+    }
+
+    async fn pinned_projects(&self) -> Result<Vec<Project>, StoreError> {
+        let fetch = self.inner.pinned_projects();
+        self.cached(&self.lists, ListKey::Pinned, fetch, |_| true)
+            .await
+    }
+
+    async fn project_by_slug(&self, slug: &str) -> Result<Option<Project>, StoreError> {
+        // Misses aren't cached, since anyone can ask for any slug
+        let key = ProjectKey::Slug(slug.to_owned());
+        let fetch = self.inner.project_by_slug(slug);
+        self.cached(&self.projects, key, fetch, Option::is_some)
+            .await
+    }
+
+    async fn project_by_id(&self, id: i32) -> Result<Option<Project>, StoreError> {
+        let fetch = self.inner.project_by_id(id);
+        self.cached(&self.projects, ProjectKey::Id(id), fetch, Option::is_some)
+            .await
+    }
+
+    async fn search_projects(
+        &self,
+        tech: Option<&str>,
+        query: Option<&str>,
+        after: Option<ProjectCursor>,
+        limit: usize,
+    ) -> Result<Vec<Project>, StoreError> {
+        let fetch = self.inner.search_projects(tech, query, after, limit);
+        if query.is_some() {
+            self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+            return fetch.await;
+        }
+
+        let key = ListKey::Search {
+            tech: tech.map(str::to_owned),
+            after,
+            limit,
+        };
+        self.cached(&self.lists, key, fetch, |_| true).await
+    }
+
+    async fn technologies(&self) -> Result<Vec<Technology>, StoreError> {
+        self.cached(&self.technologies, (), self.inner.technologies(), |_| true)
+            .await
+    }
+
+    fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.lists.clear();
+        self.projects.clear();
+        self.technologies.clear();
+        self.metrics.invalidations.fetch_add(1, Ordering::Relaxed);
+        self.inner.invalidate();
+    }
+}
+
+/// Invalidates `store` whenever the `projects_changed` trigger fires, which
+/// catches changes made outside of this server too (e.g. by the CLI).
+pub async fn listen_for_changes(pool: PgPool, store: SharedProjectStore) {
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("couldn't listen for project changes: {e}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen(CHANGES_CHANNEL).await {
+            log::error!("couldn't listen for project changes: {e}");
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        loop {
+            match listener.try_recv().await {
+                // `None` means the connection was lost, so changes may have been missed
+                Ok(_) => store.invalidate(),
+                Err(e) => {
+                    log::error!("stopped listening for project changes: {e}");
+                    store.invalidate();
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Routes for the metrics listener, which is kept off the public site since
+/// the counters are nobody else's business.
+pub fn metrics_routes(cache_metrics: Arc<CacheMetrics>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(cache_metrics)
+}
+
+/// Cache counters in the Prometheus text format.
+async fn metrics(State(metrics): State<Arc<CacheMetrics>>) -> impl IntoResponse {
+    let counters = [
+        (
+            "portfolio_project_cache_hits_total",
+            "Project queries answered from the cache.",
+            &metrics.hits,
+        ),
+        (
+            "portfolio_project_cache_misses_total",
+            "Project queries that went to the store.",
+            &metrics.misses,
+        ),
+        (
+            "portfolio_project_cache_invalidations_total",
+            "Times the project cache was cleared because projects changed.",
+            &metrics.invalidations,
+        ),
+    ];
+
+    let mut body = String::new();
+    for (name, help, counter) in counters {
+        let _ = writeln!(body, "# HELP {name} {help}");
+        let _ = writeln!(body, "# TYPE {name} counter");
+        let _ = writeln!(body, "{name} {}", counter.load(Ordering::Relaxed));
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
@@ -0,0 +1,317 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+use sqlx::PgPool;
+use thiserror::Error;
+
+use crate::app::admin::ProjectForm;
+use crate::app::projects::Project;
+use crate::config::Config;
+use crate::content::ContentError;
+use crate::fixtures::{self, Format};
+
+#[derive(Parser)]
+#[command(about = "CodeBoi's portfolio site")]
+pub struct Cli {
+    /// Apply pending migrations and exit, like `migrate`.
+    #[arg(long)]
+    pub migrate_only: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Serve the site. This is the default.
+    Serve,
+    /// Apply pending migrations and exit.
+    Migrate,
+    /// Manage projects.
+    #[command(subcommand)]
+    Project(ProjectCommand),
+    /// Import projects from a YAML or JSON fixture, updating the ones with the
+    /// same id or slug.
+    Import {
+        path: PathBuf,
+        /// Defaults to JSON for `.json` files and YAML otherwise.
+        #[arg(long)]
+        format: Option<Format>,
+        /// Only print what would change.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Create or update the projects in the content directory in the database.
+    SyncContent {
+        /// Only print what would change.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export all projects to a YAML or JSON fixture, or to stdout.
+    Export {
+        path: Option<PathBuf>,
+        /// Defaults to JSON for `.json` files and YAML otherwise.
+        #[arg(long)]
+        format: Option<Format>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProjectCommand {
+    /// List all projects, newest first.
+    List,
+    /// Create a project.
+    Add(NewProject),
+    /// Change some fields of a project.
+    Edit {
+        /// Slug or id of the project.
+        project: String,
+        #[command(flatten)]
+        changes: ProjectChanges,
+    },
+    /// Show a project on the home page.
+    Pin {
+        /// Slug or id of the project.
+        project: String,
+    },
+    /// Stop showing a project on the home page.
+    Unpin {
+        /// Slug or id of the project.
+        project: String,
+    },
+    /// Delete a project.
+    Delete {
+        /// Slug or id of the project.
+        project: String,
+    },
+}
+
+#[derive(Args)]
+pub struct NewProject {
+    #[arg(long)]
+    slug: String,
+    #[arg(long)]
+    name: String,
+    #[arg(long, default_value = "")]
+    short_description: String,
+    /// Markdown write-up. Use `--long-description-file` for longer ones.
+    #[arg(long, default_value = "", conflicts_with = "long_description_file")]
+    long_description: String,
+    #[arg(long)]
+    long_description_file: Option<PathBuf>,
+    #[arg(long, default_value = "")]
+    url: String,
+    /// Comma separated technology slugs, in display order.
+    #[arg(long, default_value = "")]
+    technologies: String,
+    #[arg(long)]
+    pinned: bool,
+}
+
+#[derive(Args)]
+pub struct ProjectChanges {
+    #[arg(long)]
+    slug: Option<String>,
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long)]
+    short_description: Option<String>,
+    #[arg(long, conflicts_with = "long_description_file")]
+    long_description: Option<String>,
+    #[arg(long)]
+    long_description_file: Option<PathBuf>,
+    /// Pass an empty URL to remove it.
+    #[arg(long)]
+    url: Option<String>,
+    /// Comma separated technology slugs, in display order.
+    #[arg(long)]
+    technologies: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("no project with the slug or id {0:?}")]
+    ProjectNotFound(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Fixture(#[from] fixtures::FixtureError),
+    #[error("no content directory is configured, set CONTENT_DIR")]
+    NoContentDir,
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("couldn't apply migrations: {0}")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+}
+
+/// Runs any command but `serve` against the database.
+pub async fn run(command: Command, pool: &PgPool, config: &Config) -> Result<(), CliError> {
+    match command {
+        Command::Serve => unreachable!("serving is handled by main"),
+        Command::Migrate => {
+            crate::db::MIGRATOR.run(pool).await?;
+            println!("migrations are up to date");
+            Ok(())
+        }
+        Command::Project(command) => run_project(command, pool).await,
+        Command::Import {
+            path,
+            format,
+            dry_run,
+        } => import(pool, &path, format, dry_run).await,
+        Command::SyncContent { dry_run } => sync_content(pool, config, dry_run).await,
+        Command::Export { path, format } => export(pool, path.as_deref(), format).await,
+    }
+}
+
+async fn run_project(command: ProjectCommand, pool: &PgPool) -> Result<(), CliError> {
+    match command {
+        ProjectCommand::List => {
+            for project in crate::db::get_projects(pool).await? {
+                println!(
+                    "{:>4}  {:<24} {}  {}{}",
+                    project.id,
+                    project.slug,
+                    project.date_created,
+                    project.name,
+                    if project.pinned { " (pinned)" } else { "" }
+                );
+            }
+        }
+        ProjectCommand::Add(new) => {
+            let long_description = match &new.long_description_file {
+                Some(path) => std::fs::read_to_string(path)?,
+                None => new.long_description,
+            };
+            let form = ProjectForm {
+                slug: new.slug,
+                name: new.name,
+                short_description: new.short_description,
+                long_description,
+                url: new.url,
+                technologies: new.technologies,
+                pinned: new.pinned,
+            };
+            form.validate().map_err(CliError::Invalid)?;
+
+            let id = crate::db::insert_project(pool, &form).await?;
+            println!("created project {id}");
+        }
+        ProjectCommand::Edit { project, changes } => {
+            let project = find_project(pool, &project).await?;
+            let mut form = ProjectForm::from(&project);
+            if let Some(slug) = changes.slug {
+                form.slug = slug;
+            }
+            if let Some(name) = changes.name {
+                form.name = name;
+            }
+            if let Some(short_description) = changes.short_description {
+                form.short_description = short_description;
+            }
+            if let Some(long_description) = changes.long_description {
+                form.long_description = long_description;
+            }
+            if let Some(path) = changes.long_description_file {
+                form.long_description = std::fs::read_to_string(path)?;
+            }
+            if let Some(url) = changes.url {
+                form.url = url;
+            }
+            if let Some(technologies) = changes.technologies {
+                form.technologies = technologies;
+            }
+            form.validate().map_err(CliError::Invalid)?;
+
+            crate::db::update_project(pool, project.id, &form).await?;
+            println!("updated project {}", project.id);
+        }
+        ProjectCommand::Pin { project } => {
+            let project = find_project(pool, &project).await?;
+            crate::db::set_project_pinned(pool, project.id, true).await?;
+        }
+        ProjectCommand::Unpin { project } => {
+            let project = find_project(pool, &project).await?;
+            crate::db::set_project_pinned(pool, project.id, false).await?;
+        }
+        ProjectCommand::Delete { project } => {
+            let project = find_project(pool, &project).await?;
+            crate::db::delete_project(pool, project.id).await?;
+            println!("deleted project {}", project.id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Looks a project up by slug, falling back to its id like old project links.
+async fn find_project(pool: &PgPool, slug_or_id: &str) -> Result<Project, CliError> {
+    if let Some(project) = crate::db::get_project_by_slug(pool, slug_or_id).await? {
+        return Ok(project);
+    }
+    let project = match slug_or_id.parse() {
+        Ok(id) => crate::db::get_project_by_id(pool, id).await?,
+        Err(_) => None,
+    };
+    project.ok_or_else(|| CliError::ProjectNotFound(slug_or_id.to_owned()))
+}
+
+async fn import(
+    pool: &PgPool,
+    path: &Path,
+    format: Option<Format>,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let format = format.unwrap_or_else(|| Format::from_path(path));
+    let projects = fixtures::deserialize(&std::fs::read_to_string(path)?, format)?;
+
+    let changes = fixtures::plan(pool, projects).await?;
+    for change in &changes {
+        print!("{}", change.describe());
+    }
+    if changes.is_empty() {
+        println!("everything is up to date");
+    } else if dry_run {
+        println!("dry run: {} projects would change", changes.len());
+    } else {
+        fixtures::apply(pool, &changes).await?;
+        println!("imported {} projects", changes.len());
+    }
+
+    Ok(())
+}
+
+async fn sync_content(pool: &PgPool, config: &Config, dry_run: bool) -> Result<(), CliError> {
+    let content_dir = config.content_dir.as_deref().ok_or(CliError::NoContentDir)?;
+    let projects = crate::content::load_projects(content_dir)?;
+
+    let changes = crate::content::sync_projects(pool, &projects, dry_run).await?;
+    for change in &changes {
+        print!("{}", change.describe());
+    }
+    if changes.is_empty() {
+        println!("everything is up to date");
+    } else if dry_run {
+        println!("dry run: {} projects would change", changes.len());
+    } else {
+        println!("synced {} projects", changes.len());
+    }
+
+    Ok(())
+}
+
+async fn export(pool: &PgPool, path: Option<&Path>, format: Option<Format>) -> Result<(), CliError> {
+    let projects = crate::db::get_projects(pool).await?;
+
+    match path {
+        Some(path) => {
+            let format = format.unwrap_or_else(|| Format::from_path(path));
+            std::fs::write(path, fixtures::serialize(&projects, format)?)?;
+        }
+        None => print!("{}", fixtures::serialize(&projects, format.unwrap_or(Format::Yaml))?),
+    }
+    Ok(())
+}
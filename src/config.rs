@@ -0,0 +1,363 @@
+use std::{
+    env,
+    fmt::Display,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::app::Analytics;
+
+/// Config file read when `CONFIG_FILE` isn't set, if it exists.
+const DEFAULT_CONFIG_FILE: &str = "portfolio.toml";
+const DEFAULT_POOL_SIZE: u32 = 4;
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+const DEFAULT_ANALYTICS_SCRIPT: &str = "https://umami.codeboi.dev/script.js";
+const DEFAULT_ANALYTICS_WEBSITE_ID: &str = "6d05defe-708b-41d7-91c1-40a55ec94f3d";
+
+/// Settings of the server, read at startup from environment variables and an
+/// optional TOML file. Environment variables take precedence over the file.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Only optional when projects are served from a content directory.
+    pub database_url: Option<String>,
+    /// Serve projects from this directory instead of the database.
+    pub content_dir: Option<PathBuf>,
+    /// Copy the content directory's projects into the database at startup.
+    pub sync_content: bool,
+    /// Reload the content directory when its files change. On by default in
+    /// debug builds only.
+    pub watch_content: bool,
+    pub pool_size: u32,
+    /// How long project queries are cached for. Zero turns the cache off.
+    pub cache_ttl: Duration,
+    pub tls_cert: PathBuf,
+    pub tls_key: PathBuf,
+    /// Analytics are left out of the pages when the website id is set to an
+    /// empty string.
+    pub analytics: Option<Analytics>,
+    pub log_level: log::Level,
+    /// Apply pending migrations before serving.
+    pub run_migrations: bool,
+    /// Path prefixes crawlers should stay away from.
+    pub robots_disallow: Vec<String>,
+    /// Where uploaded images are written and served from, at `/uploads`. Kept
+    /// out of the site root, which cargo-leptos wipes on every build.
+    pub upload_dir: PathBuf,
+    /// Account created (or reset) at startup, if both halves are set.
+    pub admin_username: Option<String>,
+    pub admin_password: Option<String>,
+    /// Serve `/metrics` on this address, apart from the public site. No metrics
+    /// are served when it isn't set.
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("couldn't read config file {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid config file {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("`{key}` is not set: set the {env} environment variable or `{key}` in the config file")]
+    Missing {
+        key: &'static str,
+        env: &'static str,
+    },
+    /// `setting` is the environment variable, or the key in the config file,
+    /// the value came from.
+    #[error("invalid value {value:?} for {setting}: {reason}")]
+    Invalid {
+        setting: String,
+        value: String,
+        reason: String,
+    },
+}
+
+/// The config file, in which every setting is optional.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    database_url: Option<String>,
+    content_dir: Option<PathBuf>,
+    sync_content: Option<bool>,
+    watch_content: Option<bool>,
+    pool_size: Option<u32>,
+    cache_ttl_secs: Option<u64>,
+    tls_cert: Option<PathBuf>,
+    tls_key: Option<PathBuf>,
+    analytics_website_id: Option<String>,
+    analytics_script_url: Option<String>,
+    log_level: Option<String>,
+    run_migrations: Option<bool>,
+    robots_disallow: Option<Vec<String>>,
+    upload_dir: Option<PathBuf>,
+    admin_username: Option<String>,
+    admin_password: Option<String>,
+    metrics_addr: Option<SocketAddr>,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(|name| env::var(name).ok())
+    }
+
+    /// Loads the config with `lookup` in place of the environment.
+    fn load_from(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        // Empty variables are treated as unset
+        let env_var = |name: &str| lookup(name).filter(|value| !value.is_empty());
+
+        let file = match env_var("CONFIG_FILE") {
+            Some(path) => FileConfig::read(Path::new(&path))?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                FileConfig::read(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => FileConfig::default(),
+        };
+
+        let content_dir = env_var("CONTENT_DIR").map(PathBuf::from).or(file.content_dir);
+        let database_url = env_var("DATABASE_URL").or(file.database_url);
+        if database_url.is_none() && content_dir.is_none() {
+            return Err(ConfigError::Missing {
+                key: "database_url",
+                env: "DATABASE_URL",
+            });
+        }
+
+        let sync_content = match env_var("SYNC_CONTENT") {
+            Some(value) => parse("SYNC_CONTENT", value)?,
+            None => file.sync_content.unwrap_or(false),
+        };
+
+        let watch_content = match env_var("WATCH_CONTENT") {
+            Some(value) => parse("WATCH_CONTENT", value)?,
+            None => file.watch_content.unwrap_or(cfg!(debug_assertions)),
+        };
+
+        let pool_size = match env_var("DATABASE_POOL_SIZE") {
+            Some(value) => parse("DATABASE_POOL_SIZE", value)?,
+            None => file.pool_size.unwrap_or(DEFAULT_POOL_SIZE),
+        };
+
+        let cache_ttl_secs = match env_var("CACHE_TTL_SECS") {
+            Some(value) => parse("CACHE_TTL_SECS", value)?,
+            None => file.cache_ttl_secs.unwrap_or(DEFAULT_CACHE_TTL_SECS),
+        };
+
+        let log_level = match (env_var("LOG_LEVEL"), file.log_level) {
+            (Some(value), _) => parse("LOG_LEVEL", value)?,
+            (None, Some(value)) => parse("`log_level` in the config file", value)?,
+            (None, None) if cfg!(debug_assertions) => log::Level::Debug,
+            (None, None) => log::Level::Warn,
+        };
+
+        let run_migrations = match env_var("RUN_MIGRATIONS") {
+            Some(value) => parse("RUN_MIGRATIONS", value)?,
+            None => file.run_migrations.unwrap_or(false),
+        };
+
+        // Unlike other settings, an empty value isn't ignored: it turns analytics off
+        let website_id = lookup("ANALYTICS_WEBSITE_ID")
+            .or(file.analytics_website_id)
+            .unwrap_or_else(|| DEFAULT_ANALYTICS_WEBSITE_ID.to_owned());
+        let analytics = (!website_id.is_empty()).then(|| Analytics {
+            script_url: env_var("ANALYTICS_SCRIPT_URL")
+                .or(file.analytics_script_url)
+                .unwrap_or_else(|| DEFAULT_ANALYTICS_SCRIPT.to_owned()),
+            website_id,
+        });
+
+        // Comma separated in the environment variable
+        let robots_disallow = match env_var("ROBOTS_DISALLOW") {
+            Some(value) => value
+                .split(',')
+                .map(|path| path.trim().to_owned())
+                .filter(|path| !path.is_empty())
+                .collect(),
+            None => file
+                .robots_disallow
+                .unwrap_or_else(|| vec!["/admin".to_owned()]),
+        };
+
+        let metrics_addr = match env_var("METRICS_ADDR") {
+            Some(value) => Some(parse("METRICS_ADDR", value)?),
+            None => file.metrics_addr,
+        };
+
+        Ok(Self {
+            database_url,
+            content_dir,
+            sync_content,
+            watch_content,
+            pool_size,
+            cache_ttl: Duration::from_secs(cache_ttl_secs),
+            tls_cert: env_var("TLS_CERT")
+                .map(PathBuf::from)
+                .or(file.tls_cert)
+                .unwrap_or_else(|| PathBuf::from("ssl/cert.pem")),
+            tls_key: env_var("TLS_KEY")
+                .map(PathBuf::from)
+                .or(file.tls_key)
+                .unwrap_or_else(|| PathBuf::from("ssl/cert.key")),
+            analytics,
+            log_level,
+            run_migrations,
+            robots_disallow,
+            upload_dir: env_var("UPLOAD_DIR")
+                .map(PathBuf::from)
+                .or(file.upload_dir)
+                .unwrap_or_else(|| PathBuf::from("uploads")),
+            admin_username: env_var("ADMIN_USERNAME").or(file.admin_username),
+            admin_password: env_var("ADMIN_PASSWORD").or(file.admin_password),
+            metrics_addr,
+        })
+    }
+}
+
+impl FileConfig {
+    fn read(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+    }
+}
+
+fn parse<T>(setting: &str, value: String) -> Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|e: T::Err| ConfigError::Invalid {
+        setting: setting.to_owned(),
+        reason: e.to_string(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    /// Loads the config with a config file of `contents` and only the given
+    /// environment variables set.
+    fn load(contents: &str, vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        // Tests run in parallel, so each gets its own file
+        static FILES: AtomicUsize = AtomicUsize::new(0);
+        let path = env::temp_dir().join(format!(
+            "portfolio-config-{}-{}.toml",
+            std::process::id(),
+            FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, contents).unwrap();
+
+        let mut env: HashMap<&str, String> = vars
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .collect();
+        env.insert("CONFIG_FILE", path.display().to_string());
+
+        let config = Config::load_from(|name| env.get(name).cloned());
+        let _ = std::fs::remove_file(&path);
+        config
+    }
+
+    const FILE: &str = r#"
+content_dir = "content"
+pool_size = 8
+cache_ttl_secs = 60
+robots_disallow = ["/private"]
+analytics_website_id = ""
+"#;
+
+    #[test]
+    fn reads_the_config_file() {
+        let config = load(FILE, &[]).unwrap();
+        assert_eq!(config.content_dir, Some(PathBuf::from("content")));
+        assert_eq!(config.pool_size, 8);
+        assert_eq!(config.cache_ttl, Duration::from_secs(60));
+        assert_eq!(config.robots_disallow, ["/private"]);
+        assert!(config.analytics.is_none());
+        assert!(config.metrics_addr.is_none());
+    }
+
+    #[test]
+    fn environment_overrides_the_file() {
+        let config = load(
+            FILE,
+            &[
+                ("DATABASE_POOL_SIZE", "16"),
+                ("ROBOTS_DISALLOW", "/admin, /drafts,"),
+                ("METRICS_ADDR", "127.0.0.1:9000"),
+                ("LOG_LEVEL", "error"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.pool_size, 16);
+        assert_eq!(config.robots_disallow, ["/admin", "/drafts"]);
+        assert_eq!(config.metrics_addr, Some(([127, 0, 0, 1], 9000).into()));
+        assert_eq!(config.log_level, log::Level::Error);
+    }
+
+    #[test]
+    fn empty_variables_are_ignored() {
+        let config = load(FILE, &[("CACHE_TTL_SECS", "")]).unwrap();
+        assert_eq!(config.cache_ttl, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn analytics_default_to_the_umami_site() {
+        let config = load("content_dir = \"content\"", &[]).unwrap();
+        let analytics = config.analytics.unwrap();
+        assert_eq!(analytics.website_id, DEFAULT_ANALYTICS_WEBSITE_ID);
+        assert_eq!(analytics.script_url, DEFAULT_ANALYTICS_SCRIPT);
+    }
+
+    #[test]
+    fn empty_website_id_turns_analytics_off() {
+        let config = load("content_dir = \"content\"", &[("ANALYTICS_WEBSITE_ID", "")]).unwrap();
+        assert!(config.analytics.is_none());
+    }
+
+    #[test]
+    fn reports_invalid_values() {
+        let result = load(FILE, &[("CACHE_TTL_SECS", "soon")]);
+        assert!(matches!(
+            result,
+            Err(ConfigError::Invalid { setting, .. }) if setting == "CACHE_TTL_SECS"
+        ));
+    }
+
+    #[test]
+    fn invalid_file_values_name_the_file_key() {
+        let result = load("content_dir = \"content\"\nlog_level = \"loud\"\n", &[]);
+        assert!(matches!(
+            result,
+            Err(ConfigError::Invalid { setting, .. }) if setting.contains("`log_level`")
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_file_settings() {
+        let result = load("content_dir = \"content\"\npool_sise = 8\n", &[]);
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+    }
+}
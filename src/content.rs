@@ -0,0 +1,469 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use notify_debouncer_mini::{
+    new_debouncer,
+    notify::{self, RecommendedWatcher, RecursiveMode},
+    DebounceEventResult, Debouncer,
+};
+
+use serde::{Deserialize, Deserializer};
+use sqlx::PgPool;
+use thiserror::Error;
+use time::{macros::format_description, Date};
+
+use crate::app::admin::ProjectForm;
+use crate::app::projects::{ImageVariant, Project, Technology};
+use crate::fixtures::{self, Change, FixtureError};
+use crate::store::FileProjectStore;
+
+#[derive(Debug, Error)]
+pub enum ContentError {
+    #[error("couldn't read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{}{}: {message}", path.display(), line.map(|line| format!(":{line}")).unwrap_or_default())]
+    Parse {
+        path: PathBuf,
+        /// Line of the file the error is on, counting from 1.
+        line: Option<usize>,
+        message: String,
+    },
+    #[error("{}: {reason}", path.display())]
+    Invalid { path: PathBuf, reason: String },
+}
+
+/// How long to wait for more changes before reloading, as editors often write
+/// a file in several steps.
+const RELOAD_DELAY: Duration = Duration::from_millis(250);
+
+/// Separates the TOML front matter of Markdown files from their body.
+const FRONT_MATTER_DELIMITER: &str = "+++";
+
+/// A project as written in a content file. Technologies are listed by slug.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectFile {
+    /// Defaults to the name of the file.
+    slug: Option<String>,
+    name: String,
+    #[serde(default)]
+    short_description: String,
+    /// Markdown write-up, which is the body of Markdown files instead.
+    #[serde(default)]
+    long_description: String,
+    url: Option<String>,
+    #[serde(default)]
+    technologies: Vec<String>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(deserialize_with = "deserialize_date")]
+    date_created: Date,
+    #[serde(default)]
+    images: Vec<ImageVariant>,
+    image_placeholder: Option<String>,
+}
+
+/// Details of the technologies listed in `{dir}/technologies.toml`.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TechnologiesFile {
+    #[serde(default)]
+    technologies: Vec<Technology>,
+}
+
+/// Reads every project in `{dir}/projects`, written as Markdown files with TOML
+/// front matter, or as `.toml` or `.json` files. Projects are given ids derived
+/// from their slugs, so they stay the same as projects are added and removed.
+pub fn load_projects(dir: &Path) -> Result<Vec<Project>, ContentError> {
+    let technologies = load_technologies(dir)?;
+    let projects_dir = dir.join("projects");
+    let io_error = |path: &Path| {
+        let path = path.to_owned();
+        move |source| ContentError::Io { path, source }
+    };
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&projects_dir)
+        .map_err(io_error(&projects_dir))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()
+        .map_err(io_error(&projects_dir))?;
+    paths.retain(|path| matches!(extension(path), Some("md" | "toml" | "json")));
+    paths.sort();
+
+    let mut projects = Vec::with_capacity(paths.len());
+    let mut slugs: HashMap<String, PathBuf> = HashMap::new();
+    for path in paths {
+        let contents = std::fs::read_to_string(&path).map_err(io_error(&path))?;
+        let project = parse_project(&path, &contents, &technologies)?;
+
+        if let Some(other) = slugs.get(&project.slug) {
+            return Err(ContentError::Invalid {
+                reason: format!("slug {:?} is already used by {}", project.slug, other.display()),
+                path,
+            });
+        }
+        slugs.insert(project.slug.clone(), path);
+        projects.push(project);
+    }
+
+    let mut ids: HashMap<i32, &str> = HashMap::new();
+    for project in &mut projects {
+        project.id = slug_id(&project.slug);
+    }
+    for project in &projects {
+        if let Some(other) = ids.insert(project.id, &project.slug) {
+            return Err(ContentError::Invalid {
+                path: slugs[&project.slug].clone(),
+                reason: format!(
+                    "slug {:?} has the same id as {other:?}; rename one of them",
+                    project.slug
+                ),
+            });
+        }
+    }
+
+    // Newest first, in the order the database would return them
+    projects.sort_by(|a, b| (b.date_created, b.id).cmp(&(a.date_created, a.id)));
+
+    Ok(projects)
+}
+
+/// A positive id for a project, from a 31-bit FNV-1a hash of its slug. Unlike
+/// `DefaultHasher`, it is the same with every build.
+fn slug_id(slug: &str) -> i32 {
+    let hash = slug.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    // Zero is left for projects that aren't saved anywhere yet
+    ((hash & 0x7fff_ffff) as i32).max(1)
+}
+
+/// Technologies with details, which projects can refer to by slug.
+fn load_technologies(dir: &Path) -> Result<Vec<Technology>, ContentError> {
+    let path = dir.join("technologies.toml");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(&path).map_err(|source| ContentError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    let file: TechnologiesFile = parse_toml(&path, &contents, 1)?;
+    Ok(file.technologies)
+}
+
+fn parse_project(
+    path: &Path,
+    contents: &str,
+    technologies: &[Technology],
+) -> Result<Project, ContentError> {
+    let file: ProjectFile = match extension(path) {
+        Some("json") => serde_json::from_str(contents).map_err(|e| ContentError::Parse {
+            path: path.to_owned(),
+            line: Some(e.line()),
+            message: e.to_string(),
+        })?,
+        Some("md") => {
+            let (front_matter, body) = split_front_matter(path, contents)?;
+            let mut file: ProjectFile = parse_toml(path, front_matter, 2)?;
+            file.long_description = body.trim().to_owned();
+            file
+        }
+        _ => parse_toml(path, contents, 1)?,
+    };
+
+    let slug = match file.slug {
+        Some(slug) => slug,
+        None => path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_owned(),
+    };
+    let project = Project {
+        id: 0,
+        slug,
+        name: file.name,
+        short_description: file.short_description,
+        long_description_html: crate::markdown::render(&file.long_description),
+        long_description: file.long_description,
+        url: file.url,
+        technologies: file
+            .technologies
+            .iter()
+            .map(|slug| technology(slug, technologies))
+            .collect(),
+        images: file.images,
+        image_placeholder: file.image_placeholder,
+        pinned: file.pinned,
+        date_created: file.date_created,
+    };
+
+    ProjectForm::from(&project)
+        .validate()
+        .map_err(|reason| ContentError::Invalid {
+            path: path.to_owned(),
+            reason,
+        })?;
+
+    Ok(project)
+}
+
+/// Reloads `store` whenever a file in `dir` changes. Content that fails to load
+/// is logged and the previous projects stay up. Changes are watched for as long
+/// as the returned debouncer is kept.
+pub fn watch(
+    dir: PathBuf,
+    store: Arc<FileProjectStore>,
+) -> Result<Debouncer<RecommendedWatcher>, notify::Error> {
+    let watched_dir = dir.clone();
+    let mut debouncer = new_debouncer(RELOAD_DELAY, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                log::error!("couldn't watch content: {e}");
+                return;
+            }
+        };
+        // Ignore the temporary files of editors
+        if !events
+            .iter()
+            .any(|event| matches!(extension(&event.path), Some("md" | "toml" | "json")))
+        {
+            return;
+        }
+
+        match load_projects(&dir) {
+            Ok(projects) => {
+                log::info!("reloaded {} projects from {}", projects.len(), dir.display());
+                store.replace(projects);
+            }
+            Err(e) => log::error!("couldn't reload content, keeping the previous version: {e}"),
+        }
+    })?;
+
+    debouncer
+        .watcher()
+        .watch(&watched_dir, RecursiveMode::Recursive)?;
+    Ok(debouncer)
+}
+
+/// Makes the `projects` table match the content directory by creating or
+/// updating projects with the same slug. Projects only in the database are kept,
+/// and so are the images of projects whose files don't list any, since those
+/// are usually uploaded through the admin area instead.
+pub async fn sync_projects(
+    pool: &PgPool,
+    projects: &[Project],
+    dry_run: bool,
+) -> Result<Vec<Change>, FixtureError> {
+    let mut synced = Vec::with_capacity(projects.len());
+    for project in projects {
+        // The ids of content projects are made up, so only match by slug
+        let mut project = Project {
+            id: 0,
+            ..project.clone()
+        };
+        if project.images.is_empty() {
+            if let Some(existing) = crate::db::get_project_by_slug(pool, &project.slug).await? {
+                project.images = existing.images;
+                project.image_placeholder = existing.image_placeholder;
+            }
+        }
+        synced.push(project);
+    }
+
+    let changes = fixtures::plan(pool, synced).await?;
+    if !dry_run {
+        fixtures::apply(pool, &changes).await?;
+    }
+    Ok(changes)
+}
+
+/// Splits a Markdown file into its front matter and body.
+fn split_front_matter<'a>(path: &Path, contents: &'a str) -> Result<(&'a str, &'a str), ContentError> {
+    let missing = |line| ContentError::Parse {
+        path: path.to_owned(),
+        line: Some(line),
+        message: format!("expected TOML front matter between `{FRONT_MATTER_DELIMITER}` lines"),
+    };
+
+    let rest = contents
+        .strip_prefix(FRONT_MATTER_DELIMITER)
+        .and_then(|rest| rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n")))
+        .ok_or_else(|| missing(1))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            return Ok((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    Err(missing(contents.lines().count()))
+}
+
+/// Parses TOML that starts on line `first_line` of the file at `path`.
+fn parse_toml<T: serde::de::DeserializeOwned>(
+    path: &Path,
+    contents: &str,
+    first_line: usize,
+) -> Result<T, ContentError> {
+    toml::from_str(contents).map_err(|e| ContentError::Parse {
+        path: path.to_owned(),
+        line: e
+            .span()
+            .map(|span| first_line + contents[..span.start].matches('\n').count()),
+        message: e.message().to_owned(),
+    })
+}
+
+/// The technology with the given slug in `technologies.toml`, or one named the
+/// way the database names technologies it doesn't know yet.
+pub(crate) fn technology(slug: &str, technologies: &[Technology]) -> Technology {
+    let slug = slug.trim().to_lowercase();
+    if let Some(technology) = technologies.iter().find(|technology| technology.slug == slug) {
+        return technology.clone();
+    }
+
+    Technology {
+        id: 0,
+        name: initcap(&slug.replace('-', " ")),
+        slug,
+        icon: None,
+        homepage_url: None,
+    }
+}
+
+/// Postgres' `initcap`: the first letter of every run of alphanumeric
+/// characters in upper case, the rest in lower case.
+fn initcap(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_word = false;
+    for ch in text.chars() {
+        if in_word {
+            output.extend(ch.to_lowercase());
+        } else {
+            output.extend(ch.to_uppercase());
+        }
+        in_word = ch.is_alphanumeric();
+    }
+    output
+}
+
+fn extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|ext| ext.to_str())
+}
+
+/// Accepts TOML dates as well as `YYYY-MM-DD` strings.
+fn deserialize_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawDate {
+        Toml(toml::value::Datetime),
+        Text(String),
+    }
+
+    let text = match RawDate::deserialize(deserializer)? {
+        RawDate::Toml(date) => date.to_string(),
+        RawDate::Text(text) => text,
+    };
+    Date::parse(&text, format_description!("[year]-[month]-[day]")).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_line(result: Result<Project, ContentError>) -> Option<usize> {
+        match result {
+            Err(ContentError::Parse { line, .. }) => line,
+            Err(e) => panic!("expected a parse error, got {e}"),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn splits_front_matter() {
+        let contents = "+++\nname = \"Portfolio\"\n+++\n\nBuilt with Leptos.\n";
+        assert_eq!(
+            split_front_matter(Path::new("portfolio.md"), contents).unwrap(),
+            ("name = \"Portfolio\"\n", "\nBuilt with Leptos.\n")
+        );
+    }
+
+    #[test]
+    fn splits_crlf_front_matter() {
+        let contents = "+++\r\nname = \"Portfolio\"\r\n+++\r\nBuilt with Leptos.\r\n";
+        assert_eq!(
+            split_front_matter(Path::new("portfolio.md"), contents).unwrap(),
+            ("name = \"Portfolio\"\r\n", "Built with Leptos.\r\n")
+        );
+    }
+
+    #[test]
+    fn reports_missing_front_matter() {
+        let path = Path::new("portfolio.md");
+        assert!(matches!(
+            split_front_matter(path, "Built with Leptos.\n"),
+            Err(ContentError::Parse { line: Some(1), .. })
+        ));
+        assert!(matches!(
+            split_front_matter(path, "+++\nname = \"Portfolio\"\n\nBuilt with Leptos.\n"),
+            Err(ContentError::Parse { line: Some(4), .. })
+        ));
+    }
+
+    #[test]
+    fn toml_errors_point_at_the_line_in_the_file() {
+        let contents = "name = \"Portfolio\"\ndate_created = 2023-09-13\nurl = nope\n";
+        let result = parse_project(Path::new("portfolio.toml"), contents, &[]);
+        assert_eq!(error_line(result), Some(3));
+    }
+
+    #[test]
+    fn front_matter_errors_count_the_opening_line() {
+        for contents in [
+            "+++\nname = \"Portfolio\"\nurl = nope\n+++\nBody\n",
+            "+++\r\nname = \"Portfolio\"\r\nurl = nope\r\n+++\r\nBody\r\n",
+        ] {
+            let result = parse_project(Path::new("portfolio.md"), contents, &[]);
+            assert_eq!(error_line(result), Some(3), "{contents:?}");
+        }
+    }
+
+    #[test]
+    fn parses_markdown_projects() {
+        let contents = "+++\r\nname = \"Portfolio\"\r\ndate_created = 2023-09-13\r\ntechnologies = [\"rust\"]\r\n+++\r\n\r\nBuilt with Leptos.\r\n";
+        let project = parse_project(Path::new("portfolio.md"), contents, &[]).unwrap();
+        assert_eq!(project.slug, "portfolio");
+        assert_eq!(project.long_description, "Built with Leptos.");
+        assert_eq!(project.technologies[0].name, "Rust");
+    }
+
+    #[test]
+    fn unknown_technologies_are_named_like_the_database_names_them() {
+        let technology = technology(" Node.JS-backend ", &[]);
+        assert_eq!(technology.slug, "node.js-backend");
+        assert_eq!(technology.name, "Node.Js Backend");
+        assert_eq!(technology.icon, None);
+    }
+
+    #[test]
+    fn ids_depend_only_on_the_slug() {
+        // Feed entries and image fallbacks refer to projects by id, so it must
+        // not change between builds
+        assert_eq!(slug_id("portfolio"), 464274147);
+        assert_ne!(slug_id("portfolio"), slug_id("portfolio-2"));
+        assert!(slug_id("portfolio") > 0);
+        assert!(slug_id("") > 0);
+    }
+}
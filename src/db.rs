@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+
+use sqlx::{migrate::Migrator, Acquire, PgConnection, PgPool, Postgres};
+use time::{Date, OffsetDateTime};
+
+use crate::app::admin::ProjectForm;
+use crate::app::blog::Post;
+use crate::app::projects::{ImageVariant, Project, ProjectCursor, Technology};
+
+/// The migrations in `migrations/`, embedded in the binary.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// A row of the `projects` table, before its technologies and images are attached.
+struct ProjectRow {
+    id: i32,
+    slug: String,
+    name: String,
+    short_description: String,
+    long_description: String,
+    url: Option<String>,
+    image_placeholder: Option<String>,
+    pinned: bool,
+    date_created: Date,
+}
+
+impl ProjectRow {
+    fn into_project(self, technologies: Vec<Technology>, images: Vec<ImageVariant>) -> Project {
+        Project {
+            id: self.id,
+            slug: self.slug,
+            name: self.name,
+            short_description: self.short_description,
+            long_description_html: crate::markdown::render(&self.long_description),
+            long_description: self.long_description,
+            url: self.url,
+            technologies,
+            images,
+            image_placeholder: self.image_placeholder,
+            pinned: self.pinned,
+            date_created: self.date_created,
+        }
+    }
+}
+
+struct ProjectTechnologyRow {
+    project_id: i32,
+    id: i32,
+    slug: String,
+    name: String,
+    icon: Option<String>,
+    homepage_url: Option<String>,
+}
+
+struct ProjectImageRow {
+    project_id: i32,
+    width: i32,
+    height: i32,
+    format: String,
+    path: String,
+}
+
+/// Fetches the technologies and image variants of every project in `rows` and
+/// attaches them. Technologies keep the order they were listed in for each project.
+async fn attach_relations(
+    pool: &PgPool,
+    rows: Vec<ProjectRow>,
+) -> Result<Vec<Project>, sqlx::Error> {
+    let project_ids: Vec<i32> = rows.iter().map(|row| row.id).collect();
+
+    let technology_rows = sqlx::query_as!(
+        ProjectTechnologyRow,
+        "SELECT pt.project_id, t.id, t.slug, t.name, t.icon, t.homepage_url
+        FROM project_technologies pt
+        JOIN technologies t ON t.id = pt.technology_id
+        WHERE pt.project_id = ANY($1)
+        ORDER BY pt.position",
+        &project_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let image_rows = sqlx::query_as!(
+        ProjectImageRow,
+        "SELECT project_id, width, height, format, path
+        FROM project_images
+        WHERE project_id = ANY($1)
+        ORDER BY width",
+        &project_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut technologies: HashMap<i32, Vec<Technology>> = HashMap::new();
+    for row in technology_rows {
+        technologies.entry(row.project_id).or_default().push(Technology {
+            id: row.id,
+            slug: row.slug,
+            name: row.name,
+            icon: row.icon,
+            homepage_url: row.homepage_url,
+        });
+    }
+
+    let mut images: HashMap<i32, Vec<ImageVariant>> = HashMap::new();
+    for row in image_rows {
+        images.entry(row.project_id).or_default().push(ImageVariant {
+            width: row.width,
+            height: row.height,
+            format: row.format,
+            path: row.path,
+        });
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let project_technologies = technologies.remove(&row.id).unwrap_or_default();
+            let project_images = images.remove(&row.id).unwrap_or_default();
+            row.into_project(project_technologies, project_images)
+        })
+        .collect())
+}
+
+pub async fn get_projects(pool: &PgPool) -> Result<Vec<Project>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        ProjectRow,
+        "SELECT id, slug, name, short_description, long_description, url, image_placeholder,
+            pinned, date_created
+        FROM projects ORDER BY date_created DESC"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    attach_relations(pool, rows).await
+}
+
+pub async fn get_pinned_projects(pool: &PgPool) -> Result<Vec<Project>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        ProjectRow,
+        "SELECT id, slug, name, short_description, long_description, url, image_placeholder,
+            pinned, date_created
+        FROM projects WHERE pinned = true ORDER BY date_created DESC"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    attach_relations(pool, rows).await
+}
+
+pub async fn get_project_by_slug(pool: &PgPool, slug: &str) -> Result<Option<Project>, sqlx::Error> {
+    let row = sqlx::query_as!(
+        ProjectRow,
+        "SELECT id, slug, name, short_description, long_description, url, image_placeholder,
+            pinned, date_created
+        FROM projects WHERE slug = $1",
+        slug
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(attach_relations(pool, row.into_iter().collect()).await?.pop())
+}
+
+pub async fn get_project_by_id(pool: &PgPool, id: i32) -> Result<Option<Project>, sqlx::Error> {
+    let row = sqlx::query_as!(
+        ProjectRow,
+        "SELECT id, slug, name, short_description, long_description, url, image_placeholder,
+            pinned, date_created
+        FROM projects WHERE id = $1",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(attach_relations(pool, row.into_iter().collect()).await?.pop())
+}
+
+/// Up to `limit` projects using the technology with slug `tech` and matching the
+/// full-text search `query`, starting right after the `after` cursor. Filters
+/// that are `None` are ignored.
+pub async fn search_projects(
+    pool: &PgPool,
+    tech: Option<&str>,
+    query: Option<&str>,
+    after: Option<ProjectCursor>,
+    limit: usize,
+) -> Result<Vec<Project>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        ProjectRow,
+        "SELECT p.id, p.slug, p.name, p.short_description, p.long_description, p.url,
+            p.image_placeholder, p.pinned, p.date_created
+        FROM projects p
+        WHERE ($1::TEXT IS NULL OR EXISTS (
+            SELECT 1 FROM project_technologies pt
+            JOIN technologies t ON t.id = pt.technology_id
+            WHERE pt.project_id = p.id AND t.slug = $1
+        ))
+        AND ($2::TEXT IS NULL OR p.search_vector @@ websearch_to_tsquery('english', $2))
+        AND ($3::DATE IS NULL OR (p.date_created, p.id) < ($3::DATE, $4::INT))
+        ORDER BY p.date_created DESC, p.id DESC
+        LIMIT $5",
+        tech,
+        query,
+        after.map(|cursor| cursor.date_created),
+        after.map(|cursor| cursor.id),
+        limit as i64
+    )
+    .fetch_all(pool)
+    .await?;
+
+    attach_relations(pool, rows).await
+}
+
+/// Every technology used by at least one project, ordered by name.
+pub async fn get_technologies(pool: &PgPool) -> Result<Vec<Technology>, sqlx::Error> {
+    sqlx::query_as!(
+        Technology,
+        "SELECT t.id, t.slug, t.name, t.icon, t.homepage_url
+        FROM technologies t
+        WHERE EXISTS (SELECT 1 FROM project_technologies pt WHERE pt.technology_id = t.id)
+        ORDER BY t.name"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Creates a project from the admin form and returns its id.
+///
+/// Like the other writes below, takes either a pool or a connection, so that
+/// it can be part of a larger transaction.
+pub async fn insert_project(
+    conn: impl Acquire<'_, Database = Postgres>,
+    project: &ProjectForm,
+) -> Result<i32, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let id = sqlx::query_scalar!(
+        "INSERT INTO projects (slug, name, short_description, long_description, url, pinned)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id",
+        project.slug.trim(),
+        project.name.trim(),
+        project.short_description.trim(),
+        project.long_description.trim(),
+        project.url(),
+        project.pinned
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    set_project_technologies(&mut tx, id, &project.technology_slugs()).await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Overwrites a project with the admin form. Returns `false` if it does not exist.
+pub async fn update_project(
+    conn: impl Acquire<'_, Database = Postgres>,
+    id: i32,
+    project: &ProjectForm,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let updated = sqlx::query!(
+        "UPDATE projects
+        SET slug = $2, name = $3, short_description = $4, long_description = $5, url = $6,
+            pinned = $7
+        WHERE id = $1",
+        id,
+        project.slug.trim(),
+        project.name.trim(),
+        project.short_description.trim(),
+        project.long_description.trim(),
+        project.url(),
+        project.pinned
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if updated {
+        set_project_technologies(&mut tx, id, &project.technology_slugs()).await?;
+    }
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+/// Replaces the technologies of a project, creating any that do not exist yet.
+async fn set_project_technologies(
+    conn: &mut PgConnection,
+    project_id: i32,
+    slugs: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO technologies (slug, name)
+        SELECT slug, initcap(replace(slug, '-', ' ')) FROM unnest($1::TEXT[]) AS slug
+        ON CONFLICT (slug) DO NOTHING",
+        slugs
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM project_technologies WHERE project_id = $1",
+        project_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO project_technologies (project_id, technology_id, position)
+        SELECT $1, t.id, tech.position::INT
+        FROM unnest($2::TEXT[]) WITH ORDINALITY AS tech (slug, position)
+        JOIN technologies t ON t.slug = tech.slug",
+        project_id,
+        slugs
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Creates technologies that don't exist yet. Existing ones keep their name and
+/// only get an icon or homepage if they have none.
+pub async fn upsert_technologies(
+    conn: impl Acquire<'_, Database = Postgres>,
+    technologies: &[Technology],
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+    for technology in technologies {
+        sqlx::query!(
+            "INSERT INTO technologies (slug, name, icon, homepage_url)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (slug) DO UPDATE
+            SET icon = COALESCE(technologies.icon, EXCLUDED.icon),
+                homepage_url = COALESCE(technologies.homepage_url, EXCLUDED.homepage_url)",
+            technology.slug,
+            technology.name,
+            technology.icon,
+            technology.homepage_url
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+/// Returns `false` if the project does not exist.
+pub async fn set_project_date_created(
+    conn: impl Acquire<'_, Database = Postgres>,
+    id: i32,
+    date: Date,
+) -> Result<bool, sqlx::Error> {
+    let mut conn = conn.acquire().await?;
+    let result = sqlx::query!("UPDATE projects SET date_created = $2 WHERE id = $1", id, date)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns `false` if the project does not exist.
+pub async fn delete_project(pool: &PgPool, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM projects WHERE id = $1", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns `false` if the project does not exist.
+pub async fn set_project_pinned(pool: &PgPool, id: i32, pinned: bool) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("UPDATE projects SET pinned = $2 WHERE id = $1", id, pinned)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Replaces the recorded image variants and placeholder of a project, returning
+/// the old variants.
+pub async fn replace_project_images(
+    conn: impl Acquire<'_, Database = Postgres>,
+    project_id: i32,
+    images: &[ImageVariant],
+    placeholder: Option<&str>,
+) -> Result<Vec<ImageVariant>, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query!(
+        "UPDATE projects SET image_placeholder = $2 WHERE id = $1",
+        project_id,
+        placeholder
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let old_images = sqlx::query_as!(
+        ImageVariant,
+        "DELETE FROM project_images WHERE project_id = $1
+        RETURNING width, height, format, path",
+        project_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for image in images {
+        sqlx::query!(
+            "INSERT INTO project_images (project_id, width, height, format, path)
+            VALUES ($1, $2, $3, $4, $5)",
+            project_id,
+            image.width,
+            image.height,
+            image.format,
+            image.path
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(old_images)
+}
+
+/// A row of the `posts` table, before its body is rendered.
+struct PostRow {
+    id: i32,
+    slug: String,
+    title: String,
+    summary: String,
+    body: String,
+    tags: Vec<String>,
+    published: bool,
+    published_at: Option<OffsetDateTime>,
+    date_created: Date,
+}
+
+impl PostRow {
+    fn into_post(self) -> Post {
+        Post {
+            id: self.id,
+            slug: self.slug,
+            title: self.title,
+            summary: self.summary,
+            body_html: crate::markdown::render(&self.body),
+            body: self.body,
+            tags: self.tags,
+            published: self.published,
+            published_at: self.published_at,
+            date_created: self.date_created,
+        }
+    }
+}
+
+/// Published posts, newest first, up to `limit` of them if given.
+pub async fn get_posts(pool: &PgPool, limit: Option<i64>) -> Result<Vec<Post>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        PostRow,
+        "SELECT id, slug, title, summary, body, tags, published, published_at, date_created
+        FROM posts
+        WHERE published
+        ORDER BY published_at DESC NULLS LAST, id DESC
+        LIMIT $1",
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(PostRow::into_post).collect())
+}
+
+/// The published post with the given slug, if any.
+pub async fn get_post(pool: &PgPool, slug: &str) -> Result<Option<Post>, sqlx::Error> {
+    let row = sqlx::query_as!(
+        PostRow,
+        "SELECT id, slug, title, summary, body, tags, published, published_at, date_created
+        FROM posts
+        WHERE slug = $1 AND published",
+        slug
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(PostRow::into_post))
+}
@@ -0,0 +1,201 @@
+use atom_syndication::{Content, Entry, Feed, FixedDateTime, Link, Person, Text};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use rss::{ChannelBuilder, GuidBuilder, ItemBuilder};
+use sqlx::PgPool;
+use time::{
+    format_description::well_known::{Rfc2822, Rfc3339},
+    OffsetDateTime,
+};
+
+use crate::app::SITE_URL;
+use crate::state::AppState;
+use crate::store::SharedProjectStore;
+
+const FEED_TITLE: &str = "CodeBoi";
+const FEED_DESCRIPTION: &str = "New projects and posts from CodeBoi";
+const MAX_FEED_ITEMS: usize = 50;
+
+/// A project or post, in the shape both feed formats need.
+struct FeedItem {
+    /// Never changes, even when the item's slug or title does.
+    id: String,
+    title: String,
+    link: String,
+    summary: String,
+    content_html: String,
+    date: OffsetDateTime,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/feed.xml", get(atom_feed))
+        .route("/rss.xml", get(rss_feed))
+}
+
+/// Projects and published posts, newest first. Posts are left out when there is
+/// no database.
+async fn feed_items(
+    store: &SharedProjectStore,
+    pool: Option<&PgPool>,
+) -> Result<Vec<FeedItem>, (StatusCode, String)> {
+    let projects = store.projects().await.map_err(internal_error)?;
+    let posts = match pool {
+        Some(pool) => crate::db::get_posts(pool, Some(MAX_FEED_ITEMS as i64))
+            .await
+            .map_err(internal_error)?,
+        None => Vec::new(),
+    };
+
+    let mut items: Vec<FeedItem> = projects
+        .into_iter()
+        .map(|project| FeedItem {
+            id: format!("tag:codeboi.dev,{}:project/{}", project.date_created, project.id),
+            title: project.name,
+            link: format!("{SITE_URL}/projects/{}", project.slug),
+            summary: project.short_description,
+            content_html: project.long_description_html,
+            date: project.date_created.midnight().assume_utc(),
+        })
+        .chain(posts.into_iter().map(|post| FeedItem {
+            id: format!("tag:codeboi.dev,{}:post/{}", post.date_created, post.id),
+            link: format!("{SITE_URL}/blog/{}", post.slug),
+            date: post
+                .published_at
+                .unwrap_or_else(|| post.date_created.midnight().assume_utc()),
+            title: post.title,
+            summary: post.summary,
+            content_html: post.body_html,
+        }))
+        .collect();
+
+    items.sort_by(|a, b| b.date.cmp(&a.date));
+    items.truncate(MAX_FEED_ITEMS);
+    Ok(items)
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Something went wrong: {e}"),
+    )
+}
+
+fn atom_date(date: OffsetDateTime) -> Result<FixedDateTime, (StatusCode, String)> {
+    let date = date.format(&Rfc3339).map_err(internal_error)?;
+    FixedDateTime::parse_from_rfc3339(&date).map_err(internal_error)
+}
+
+async fn atom_feed(
+    State(store): State<SharedProjectStore>,
+    State(pool): State<Option<PgPool>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let items = feed_items(&store, pool.as_ref()).await?;
+
+    let entries = items
+        .into_iter()
+        .map(|item| {
+            Ok(Entry {
+                title: Text::plain(item.title),
+                id: item.id,
+                updated: atom_date(item.date)?,
+                links: vec![Link {
+                    href: item.link,
+                    ..Default::default()
+                }],
+                summary: Some(Text::plain(item.summary)),
+                content: Some(Content {
+                    value: Some(item.content_html),
+                    content_type: Some("html".to_owned()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let updated = match entries.first() {
+        Some(entry) => entry.updated,
+        None => atom_date(OffsetDateTime::UNIX_EPOCH)?,
+    };
+
+    let feed = Feed {
+        title: Text::plain(FEED_TITLE),
+        subtitle: Some(Text::plain(FEED_DESCRIPTION)),
+        id: format!("{SITE_URL}/"),
+        updated,
+        authors: vec![Person {
+            name: FEED_TITLE.to_owned(),
+            uri: Some(format!("{SITE_URL}/")),
+            ..Default::default()
+        }],
+        links: vec![
+            Link {
+                href: format!("{SITE_URL}/"),
+                ..Default::default()
+            },
+            Link {
+                href: format!("{SITE_URL}/feed.xml"),
+                rel: "self".to_owned(),
+                mime_type: Some("application/atom+xml".to_owned()),
+                ..Default::default()
+            },
+        ],
+        entries,
+        ..Default::default()
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        feed.to_string(),
+    ))
+}
+
+async fn rss_feed(
+    State(store): State<SharedProjectStore>,
+    State(pool): State<Option<PgPool>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let items = feed_items(&store, pool.as_ref()).await?;
+    let last_build_date = items
+        .first()
+        .map(|item| item.date.format(&Rfc2822))
+        .transpose()
+        .map_err(internal_error)?;
+
+    let items = items
+        .into_iter()
+        .map(|item| {
+            Ok(ItemBuilder::default()
+                .title(item.title)
+                .link(item.link)
+                .description(item.summary)
+                .content(item.content_html)
+                .guid(
+                    GuidBuilder::default()
+                        .value(item.id)
+                        .permalink(false)
+                        .build(),
+                )
+                .pub_date(item.date.format(&Rfc2822).map_err(internal_error)?)
+                .build())
+        })
+        .collect::<Result<Vec<_>, (StatusCode, String)>>()?;
+
+    let channel = ChannelBuilder::default()
+        .title(FEED_TITLE)
+        .link(format!("{SITE_URL}/"))
+        .description(FEED_DESCRIPTION)
+        .last_build_date(last_build_date)
+        .items(items)
+        .build();
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        channel.to_string(),
+    ))
+}
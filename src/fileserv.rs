@@ -5,32 +5,63 @@ cfg_if! { if #[cfg(feature = "ssr")] {
         body::Body,
         extract::State,
         response::IntoResponse,
-        http::{Request, Response, StatusCode, Uri},
+        http::{header, HeaderMap, HeaderValue, Request, Response, StatusCode, Uri},
     };
     use axum::response::Response as AxumResponse;
     use tower::ServiceExt;
     use tower_http::services::ServeDir;
     use leptos::*;
     use crate::app::App;
+    use crate::store::SharedProjectStore;
 
-    pub async fn file_and_error_handler(uri: Uri, State(options): State<LeptosOptions>, req: Request<Body>) -> AxumResponse {
+    pub async fn file_and_error_handler(uri: Uri, State(options): State<LeptosOptions>, State(projects): State<SharedProjectStore>, req: Request<Body>) -> AxumResponse {
         let root = options.site_root.clone();
-        let res = get_static_file(uri.clone(), &root).await.unwrap();
+        let mut res = get_static_file(uri.clone(), req.headers(), &root).await.unwrap();
 
-        if res.status() == StatusCode::OK {
+        // Screenshots put in place by hand used to be named after the project's id
+        if res.status() == StatusCode::NOT_FOUND {
+            if let Some(legacy_uri) = legacy_image_uri(&uri, &projects).await {
+                res = get_static_file(legacy_uri, req.headers(), &root).await.unwrap();
+            }
+        }
+
+        if matches!(res.status(), StatusCode::OK | StatusCode::NOT_MODIFIED) {
             res.into_response()
         } else {
-            let handler = leptos_axum::render_app_to_stream(options.to_owned(), move || view!{<App/>});
+            let context_options = options.clone();
+            let handler = leptos_axum::render_app_to_stream_with_context(
+                options.to_owned(),
+                move || provide_context(context_options.clone()),
+                move || view!{<App/>},
+            );
             handler(req).await.into_response()
         }
     }
 
-    async fn get_static_file(uri: Uri, root: &str) -> Result<Response<Body>, (StatusCode, String)> {
-        let req = Request::builder().uri(uri.clone()).body(Body::empty()).unwrap();
+    /// `/images/projects/{id}.png` for a request of `/images/projects/{slug}.png`.
+    async fn legacy_image_uri(uri: &Uri, projects: &SharedProjectStore) -> Option<Uri> {
+        let slug = uri.path().strip_prefix("/images/projects/")?.strip_suffix(".png")?;
+        let project = projects.project_by_slug(slug).await.ok()??;
+        format!("/images/projects/{}.png", project.id).parse().ok()
+    }
+
+    async fn get_static_file(uri: Uri, headers: &HeaderMap, root: &str) -> Result<Response<Body>, (StatusCode, String)> {
+        let mut req = Request::builder().uri(uri.clone()).body(Body::empty()).unwrap();
+        // Keep the conditional headers, so unchanged files are answered with a 304
+        *req.headers_mut() = headers.clone();
         // `ServeDir` implements `tower::Service` so we can call it with `tower::ServiceExt::oneshot`
         // This path is relative to the cargo root
-        match ServeDir::new(root).oneshot(req).await {
-            Ok(res) => Ok(res.map(Body::new)),
+        // Files with a `.br` or `.gz` sibling are sent compressed when the client accepts it
+        match ServeDir::new(root).precompressed_br().precompressed_gzip().oneshot(req).await {
+            Ok(res) => {
+                let mut res = res.map(Body::new);
+                // `ServeDir` doesn't mark precompressed files as depending on `Accept-Encoding`,
+                // and the compression layer leaves responses that are already encoded alone
+                if res.headers().contains_key(header::CONTENT_ENCODING) {
+                    res.headers_mut().append(header::VARY, HeaderValue::from_static("accept-encoding"));
+                }
+                Ok(res)
+            }
             Err(err) => Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Something went wrong: {err}"),
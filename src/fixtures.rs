@@ -0,0 +1,450 @@
+use std::{fmt::Write, path::Path};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use thiserror::Error;
+use time::Date;
+
+use crate::app::admin::ProjectForm;
+use crate::app::projects::{ImageVariant, Project};
+
+/// File format of a fixture.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Guesses the format from a file extension, defaulting to YAML.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FixtureError {
+    #[error("project {slug:?}: {reason}")]
+    Invalid { slug: String, reason: String },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A project as written in a fixture: only the fields it is edited by, with its
+/// technologies by slug. Ids and rendered HTML are left to the database.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureProject {
+    slug: String,
+    name: String,
+    short_description: String,
+    /// Markdown source of the project's write-up.
+    long_description: String,
+    url: Option<String>,
+    #[serde(default)]
+    technologies: Vec<String>,
+    #[serde(default)]
+    pinned: bool,
+    date_created: Date,
+    #[serde(default)]
+    images: Vec<ImageVariant>,
+    #[serde(default)]
+    image_placeholder: Option<String>,
+}
+
+impl From<&Project> for FixtureProject {
+    fn from(project: &Project) -> Self {
+        Self {
+            slug: project.slug.clone(),
+            name: project.name.clone(),
+            short_description: project.short_description.clone(),
+            long_description: project.long_description.clone(),
+            url: project.url.clone(),
+            technologies: project
+                .technologies
+                .iter()
+                .map(|technology| technology.slug.clone())
+                .collect(),
+            pinned: project.pinned,
+            date_created: project.date_created,
+            images: project.images.clone(),
+            image_placeholder: project.image_placeholder.clone(),
+        }
+    }
+}
+
+impl From<FixtureProject> for Project {
+    fn from(fixture: FixtureProject) -> Self {
+        Self {
+            id: 0,
+            slug: fixture.slug,
+            name: fixture.name,
+            short_description: fixture.short_description,
+            long_description_html: crate::markdown::render(&fixture.long_description),
+            long_description: fixture.long_description,
+            url: fixture.url,
+            technologies: fixture
+                .technologies
+                .iter()
+                .map(|slug| crate::content::technology(slug, &[]))
+                .collect(),
+            images: fixture.images,
+            image_placeholder: fixture.image_placeholder,
+            pinned: fixture.pinned,
+            date_created: fixture.date_created,
+        }
+    }
+}
+
+pub fn serialize(projects: &[Project], format: Format) -> Result<String, FixtureError> {
+    let fixtures: Vec<FixtureProject> = projects.iter().map(FixtureProject::from).collect();
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(&fixtures)?,
+        Format::Yaml => serde_yaml::to_string(&fixtures)?,
+    })
+}
+
+pub fn deserialize(contents: &str, format: Format) -> Result<Vec<Project>, FixtureError> {
+    let fixtures: Vec<FixtureProject> = match format {
+        Format::Json => serde_json::from_str(contents)?,
+        Format::Yaml => serde_yaml::from_str(contents)?,
+    };
+    Ok(fixtures.into_iter().map(Project::from).collect())
+}
+
+/// What importing a project would change.
+pub enum Change {
+    Create(Project),
+    Update {
+        existing: Project,
+        project: Project,
+        fields: Vec<Field>,
+    },
+}
+
+/// A field of a project that importing can change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    ShortDescription,
+    LongDescription,
+    Url,
+    Technologies,
+    Pinned,
+    DateCreated,
+    /// The image variants and their placeholder.
+    Images,
+}
+
+/// Longest value [`Change::describe`] shows in full.
+const MAX_DESCRIBED_LEN: usize = 60;
+
+impl Change {
+    /// One line per project, plus one per changed field with its old and new value.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Create(project) => format!("+ {} (new project)\n", project.slug),
+            Self::Update {
+                existing,
+                project,
+                fields,
+            } => {
+                let mut description = format!("~ {} ({})\n", existing.slug, existing.id);
+                for field in fields {
+                    let _ = writeln!(
+                        description,
+                        "    {}: {} -> {}",
+                        field.name(),
+                        field.describe(existing),
+                        field.describe(project)
+                    );
+                }
+                description
+            }
+        }
+    }
+}
+
+impl Field {
+    const ALL: [Self; 8] = [
+        Self::Name,
+        Self::ShortDescription,
+        Self::LongDescription,
+        Self::Url,
+        Self::Technologies,
+        Self::Pinned,
+        Self::DateCreated,
+        Self::Images,
+    ];
+
+    /// The field's key in fixtures.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::ShortDescription => "short_description",
+            Self::LongDescription => "long_description",
+            Self::Url => "url",
+            Self::Technologies => "technologies",
+            Self::Pinned => "pinned",
+            Self::DateCreated => "date_created",
+            Self::Images => "images",
+        }
+    }
+
+    fn differs(self, existing: &Project, project: &Project) -> bool {
+        match self {
+            Self::Name => existing.name != project.name,
+            Self::ShortDescription => existing.short_description != project.short_description,
+            Self::LongDescription => existing.long_description != project.long_description,
+            Self::Url => existing.url != project.url,
+            // Names and icons are the technology's own, not the project's
+            Self::Technologies => {
+                let slugs = |project: &Project| -> Vec<String> {
+                    project
+                        .technologies
+                        .iter()
+                        .map(|technology| technology.slug.clone())
+                        .collect()
+                };
+                slugs(existing) != slugs(project)
+            }
+            Self::Pinned => existing.pinned != project.pinned,
+            Self::DateCreated => existing.date_created != project.date_created,
+            Self::Images => {
+                existing.images != project.images
+                    || existing.image_placeholder != project.image_placeholder
+            }
+        }
+    }
+
+    /// A project's value of the field, on one line and shortened if it is long.
+    fn describe(self, project: &Project) -> String {
+        let value = match self {
+            Self::Name => format!("{:?}", project.name),
+            Self::ShortDescription => format!("{:?}", project.short_description),
+            Self::LongDescription => format!("{:?}", project.long_description),
+            Self::Url => match &project.url {
+                Some(url) => format!("{url:?}"),
+                None => "none".to_owned(),
+            },
+            Self::Technologies => {
+                let slugs: Vec<&str> = project
+                    .technologies
+                    .iter()
+                    .map(|technology| technology.slug.as_str())
+                    .collect();
+                format!("[{}]", slugs.join(", "))
+            }
+            Self::Pinned => project.pinned.to_string(),
+            Self::DateCreated => project.date_created.to_string(),
+            Self::Images => match project.image_placeholder {
+                Some(_) => format!("{} variants with a placeholder", project.images.len()),
+                None => format!("{} variants", project.images.len()),
+            },
+        };
+
+        if value.chars().count() > MAX_DESCRIBED_LEN {
+            let shortened: String = value.chars().take(MAX_DESCRIBED_LEN).collect();
+            format!("{shortened}...")
+        } else {
+            value
+        }
+    }
+}
+
+/// Compares fixture projects to the database, matching them by slug. Unchanged
+/// projects are left out.
+pub async fn plan(pool: &PgPool, projects: Vec<Project>) -> Result<Vec<Change>, FixtureError> {
+    let mut changes = Vec::new();
+
+    for project in projects {
+        ProjectForm::from(&project)
+            .validate()
+            .map_err(|reason| FixtureError::Invalid {
+                slug: project.slug.clone(),
+                reason,
+            })?;
+
+        match crate::db::get_project_by_slug(pool, &project.slug).await? {
+            None => changes.push(Change::Create(project)),
+            Some(existing) => {
+                let fields = changed_fields(&existing, &project);
+                if !fields.is_empty() {
+                    changes.push(Change::Update {
+                        existing,
+                        project,
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+fn changed_fields(existing: &Project, project: &Project) -> Vec<Field> {
+    Field::ALL
+        .into_iter()
+        .filter(|field| field.differs(existing, project))
+        .collect()
+}
+
+/// Writes planned changes to the database, all of them or none if one fails.
+pub async fn apply(pool: &PgPool, changes: &[Change]) -> Result<(), FixtureError> {
+    let mut tx = pool.begin().await?;
+
+    for change in changes {
+        let (id, project) = match change {
+            Change::Create(project) => {
+                let id = crate::db::insert_project(&mut *tx, &ProjectForm::from(project)).await?;
+                (id, project)
+            }
+            Change::Update {
+                existing, project, ..
+            } => {
+                crate::db::update_project(&mut *tx, existing.id, &ProjectForm::from(project))
+                    .await?;
+                (existing.id, project)
+            }
+        };
+
+        crate::db::upsert_technologies(&mut *tx, &project.technologies).await?;
+        crate::db::set_project_date_created(&mut *tx, id, project.date_created).await?;
+        // The image files themselves are expected to be in place already
+        crate::db::replace_project_images(
+            &mut *tx,
+            id,
+            &project.images,
+            project.image_placeholder.as_deref(),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    fn project(slug: &str) -> Project {
+        Project {
+            id: 0,
+            slug: slug.to_owned(),
+            name: "Portfolio".to_owned(),
+            short_description: "My portfolio website".to_owned(),
+            long_description: "Built with Leptos.".to_owned(),
+            long_description_html: String::new(),
+            url: None,
+            technologies: vec![Technology {
+                id: 0,
+                slug: "rust".to_owned(),
+                name: "Rust".to_owned(),
+                icon: Some("rust".to_owned()),
+                homepage_url: None,
+            }],
+            images: Vec::new(),
+            image_placeholder: None,
+            pinned: false,
+            date_created: date!(2023 - 09 - 13),
+        }
+    }
+
+    #[test]
+    fn ids_and_technology_details_are_not_compared() {
+        let mut existing = project("portfolio");
+        existing.id = 3;
+        existing.technologies[0].id = 7;
+        existing.technologies[0].name = "Rust (the language)".to_owned();
+        assert!(changed_fields(&existing, &project("portfolio")).is_empty());
+    }
+
+    #[test]
+    fn fixtures_only_contain_source_fields() {
+        let mut exported = project("portfolio");
+        exported.id = 3;
+        exported.long_description_html = "<p>Built with Leptos.</p>".to_owned();
+        let json = serialize(&[exported], Format::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value[0].get("id").is_none(), "{json}");
+        assert!(value[0].get("long_description_html").is_none(), "{json}");
+        assert_eq!(value[0]["technologies"], serde_json::json!(["rust"]));
+
+        let imported = deserialize(&json, Format::Json).unwrap();
+        assert!(changed_fields(&project("portfolio"), &imported[0]).is_empty());
+    }
+
+    #[test]
+    fn changed_fields_are_listed() {
+        let mut changed = project("portfolio");
+        changed.name = "CodeBoi".to_owned();
+        changed.pinned = true;
+        changed.image_placeholder = Some("data:image/png;base64,".to_owned());
+        assert_eq!(
+            changed_fields(&project("portfolio"), &changed),
+            [Field::Name, Field::Pinned, Field::Images]
+        );
+    }
+
+    #[test]
+    fn describe_shows_old_and_new_values() {
+        let existing = Project {
+            id: 3,
+            ..project("portfolio")
+        };
+        let mut updated = project("portfolio");
+        updated.name = "CodeBoi".to_owned();
+        updated.long_description = "x".repeat(100);
+
+        let change = Change::Update {
+            fields: changed_fields(&existing, &updated),
+            existing,
+            project: updated,
+        };
+        assert_eq!(
+            change.describe(),
+            format!(
+                "~ portfolio (3)\n    name: \"Portfolio\" -> \"CodeBoi\"\n    long_description: \"Built with Leptos.\" -> \"{}...\n",
+                "x".repeat(MAX_DESCRIBED_LEN - 1)
+            )
+        );
+    }
+
+    #[sqlx::test]
+    async fn plan_matches_by_slug(pool: PgPool) -> Result<(), FixtureError> {
+        let changes = plan(&pool, vec![project("portfolio")]).await?;
+        assert!(matches!(changes.as_slice(), [Change::Create(_)]));
+        apply(&pool, &changes).await?;
+
+        assert!(plan(&pool, vec![project("portfolio")]).await?.is_empty());
+
+        let mut renamed = project("portfolio");
+        renamed.name = "CodeBoi".to_owned();
+        let changes = plan(&pool, vec![renamed]).await?;
+        assert!(matches!(
+            changes.as_slice(),
+            [Change::Update { fields, .. }] if fields == &[Field::Name]
+        ));
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn plan_rejects_invalid_projects(pool: PgPool) {
+        let result = plan(&pool, vec![project("Not a slug")]).await;
+        assert!(matches!(result, Err(FixtureError::Invalid { .. })));
+    }
+}
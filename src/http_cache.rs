@@ -0,0 +1,262 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::{stream, StreamExt};
+
+/// For files whose URL changes whenever their contents do.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// For everything else: caches may keep a copy, but must check it is current.
+const REVALIDATE: &str = "no-cache";
+const PRIVATE: &str = "private, no-store";
+/// Largest response that is buffered to compute its `ETag`.
+const MAX_ETAG_BODY: usize = 1024 * 1024;
+
+/// Sets `Cache-Control` on every response, gives responses of a known length
+/// (like those of read-only server functions, which use GET) and pages an
+/// `ETag`, and answers `If-None-Match` with 304 when the response hasn't changed.
+///
+/// Pages rendered by Leptos are streamed, so they are held back until they are
+/// done to be hashed. Ones over `MAX_ETAG_BODY` are sent on without an `ETag`.
+pub async fn http_cache(req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_owned();
+    let cacheable_method = matches!(*req.method(), Method::GET | Method::HEAD);
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+
+    let mut res = next.run(req).await;
+
+    let private = path.starts_with("/admin") || res.headers().contains_key(header::SET_COOKIE);
+    if !res.headers().contains_key(header::CACHE_CONTROL) {
+        let cache_control = if private {
+            PRIVATE
+        // Not a missing file though, such as the build output of an older deploy
+        } else if is_immutable(&path) && !res.status().is_client_error() {
+            IMMUTABLE
+        } else {
+            REVALIDATE
+        };
+        res.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+    }
+
+    // Static files are revalidated with the `Last-Modified` date `ServeDir` sets
+    if private
+        || !cacheable_method
+        || res.status() != StatusCode::OK
+        || res.headers().contains_key(header::ETAG)
+        || res.headers().contains_key(header::LAST_MODIFIED)
+        || !(is_html(&res) || has_small_body(&res))
+    {
+        return res;
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let body = match buffer(body).await {
+        Ok(Buffered::Whole(body)) => body,
+        Ok(Buffered::TooLarge(body)) => return Response::from_parts(parts, body),
+        Err(e) => {
+            log::error!("couldn't buffer response to {path}: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    // Weak, as compression changes the bytes but not what they mean
+    let etag = HeaderValue::from_str(&format!("W/\"{:016x}\"", hasher.finish()))
+        .expect("hex digits are a valid header value");
+
+    if if_none_match.is_some_and(|tags| matches_etag(&tags, &etag)) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        for name in [header::CACHE_CONTROL, header::VARY] {
+            if let Some(value) = parts.headers.get(&name) {
+                not_modified.headers_mut().insert(name, value.clone());
+            }
+        }
+        not_modified.headers_mut().insert(header::ETAG, etag);
+        return not_modified;
+    }
+
+    parts.headers.insert(header::ETAG, etag);
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn is_html(res: &Response) -> bool {
+    res.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+fn has_small_body(res: &Response) -> bool {
+    res.body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len as usize <= MAX_ETAG_BODY)
+}
+
+enum Buffered {
+    Whole(Bytes),
+    /// The body went over `MAX_ETAG_BODY`: what was read, followed by the rest.
+    TooLarge(Body),
+}
+
+/// Reads `body` to the end, unless it turns out to be over `MAX_ETAG_BODY`.
+async fn buffer(body: Body) -> Result<Buffered, axum::Error> {
+    let mut chunks = body.into_data_stream();
+    let mut buffered = Vec::new();
+    while let Some(chunk) = chunks.next().await {
+        buffered.extend_from_slice(&chunk?);
+        if buffered.len() > MAX_ETAG_BODY {
+            let read = stream::once(async { Ok(Bytes::from(buffered)) });
+            return Ok(Buffered::TooLarge(Body::from_stream(read.chain(chunks))));
+        }
+    }
+    Ok(Buffered::Whole(Bytes::from(buffered)))
+}
+
+/// Files that are never changed in place: the build output, which cargo-leptos
+/// names after a hash of its contents (`hash-files`), the fonts, and uploaded
+/// images, which are named after a hash of the upload.
+fn is_immutable(path: &str) -> bool {
+    path.starts_with("/pkg/") || path.starts_with("/fonts/") || path.starts_with("/uploads/")
+}
+
+/// Whether an `If-None-Match` header lists `etag`, compared weakly.
+fn matches_etag(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(tags) = if_none_match.to_str() else {
+        return false;
+    };
+    let etag = etag.to_str().unwrap_or_default().trim_start_matches("W/");
+    tags.split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{middleware, routing::get, Router};
+    use leptos::{provide_context, LeptosOptions};
+    use leptos_axum::{generate_route_list, LeptosRoutes};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::app::App;
+    use crate::store::{FileProjectStore, SharedProjectStore};
+
+    /// The site's pages, rendered by Leptos like in `main`, with no projects.
+    fn pages() -> Router {
+        let options = LeptosOptions::builder().output_name("portfolio").build();
+        let projects: SharedProjectStore = Arc::new(FileProjectStore::new(Vec::new()));
+        let routes = generate_route_list(App);
+        Router::new()
+            .leptos_routes_with_context(
+                &options,
+                routes,
+                move || provide_context(projects.clone()),
+                App,
+            )
+            .layer(middleware::from_fn(http_cache))
+            .with_state(options)
+    }
+
+    fn request(uri: &str, if_none_match: Option<&HeaderValue>) -> Request {
+        let mut req = axum::http::Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        if let Some(etag) = if_none_match {
+            req.headers_mut()
+                .insert(header::IF_NONE_MATCH, etag.clone());
+        }
+        req
+    }
+
+    #[tokio::test]
+    async fn rendered_pages_get_an_etag() {
+        let res = pages().oneshot(request("/projects", None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let etag = res.headers().get(header::ETAG).expect("no ETag").clone();
+
+        let res = pages()
+            .oneshot(request("/projects", Some(&etag)))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(header::ETAG), Some(&etag));
+        assert_eq!(
+            res.headers().get(header::CACHE_CONTROL).unwrap(),
+            REVALIDATE
+        );
+    }
+
+    #[tokio::test]
+    async fn large_pages_are_streamed_without_an_etag() {
+        const CHUNK: usize = 64 * 1024;
+        let chunks = MAX_ETAG_BODY / CHUNK + 2;
+        let app = Router::new()
+            .route(
+                "/",
+                get(move || async move {
+                    let body =
+                        stream::iter((0..chunks).map(|_| Ok::<_, axum::Error>(vec![b'x'; CHUNK])));
+                    (
+                        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                        Body::from_stream(body),
+                    )
+                }),
+            )
+            .layer(middleware::from_fn(http_cache));
+
+        let res = app.oneshot(request("/", None)).await.unwrap();
+        assert!(!res.headers().contains_key(header::ETAG));
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.len(), chunks * CHUNK);
+    }
+
+    #[test]
+    fn immutable_paths() {
+        assert!(is_immutable("/fonts/UbuntuMono-Regular.ttf"));
+        assert!(is_immutable(
+            "/uploads/projects/portfolio/480-2f1cbd7a9e0c4d11.webp"
+        ));
+        assert!(is_immutable("/pkg/portfolio.Kv3jS9xBZyQ1.wasm"));
+        assert!(is_immutable("/pkg/portfolio.Kv3jS9xBZyQ1.css"));
+        assert!(!is_immutable("/images/projects/portfolio.png"));
+        assert!(!is_immutable("/projects"));
+    }
+
+    fn matches(if_none_match: &'static str, etag: &'static str) -> bool {
+        matches_etag(
+            &HeaderValue::from_static(if_none_match),
+            &HeaderValue::from_static(etag),
+        )
+    }
+
+    #[test]
+    fn etags_are_compared_weakly() {
+        assert!(matches(r#"W/"abc""#, r#"W/"abc""#));
+        assert!(matches(r#""abc""#, r#"W/"abc""#));
+        assert!(matches(r#"W/"abc""#, r#""abc""#));
+        assert!(matches(r#""abc""#, r#""abc""#));
+        assert!(!matches(r#"W/"abd""#, r#"W/"abc""#));
+    }
+
+    #[test]
+    fn any_listed_etag_matches() {
+        assert!(matches(r#""xyz", W/"abc""#, r#"W/"abc""#));
+        assert!(matches("*", r#"W/"abc""#));
+        assert!(!matches(r#""xyz", "uvw""#, r#"W/"abc""#));
+    }
+}
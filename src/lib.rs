@@ -1,7 +1,39 @@
 use cfg_if::cfg_if;
 pub mod app;
+#[cfg(feature = "ssr")]
+pub mod auth;
+#[cfg(feature = "ssr")]
+pub mod cache;
+#[cfg(feature = "ssr")]
+pub mod cli;
+#[cfg(feature = "ssr")]
+pub mod config;
+#[cfg(feature = "ssr")]
+pub mod content;
+#[cfg(feature = "ssr")]
+pub mod db;
 pub mod error_template;
+#[cfg(feature = "ssr")]
+pub mod escape;
+#[cfg(feature = "ssr")]
+pub mod feeds;
 pub mod fileserv;
+#[cfg(feature = "ssr")]
+pub mod fixtures;
+#[cfg(feature = "ssr")]
+pub mod http_cache;
+#[cfg(feature = "ssr")]
+pub mod markdown;
+#[cfg(feature = "ssr")]
+pub mod og_image;
+#[cfg(feature = "ssr")]
+pub mod sitemap;
+#[cfg(feature = "ssr")]
+pub mod state;
+#[cfg(feature = "ssr")]
+pub mod store;
+#[cfg(feature = "ssr")]
+pub mod uploads;
 
 cfg_if! { if #[cfg(feature = "hydrate")] {
     use leptos::*;
@@ -2,19 +2,24 @@ use cfg_if::cfg_if;
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
-        #[macro_use]
-        extern crate dotenv_codegen;
-
         use axum::Router;
+        use clap::Parser;
         use leptos::*;
         use leptos_axum::{generate_route_list, LeptosRoutes};
         use portfolio::app::*;
+        use portfolio::cache::{CacheMetrics, CachedProjectStore};
+        use portfolio::cli::{Cli, Command};
+        use portfolio::config::Config;
         use portfolio::fileserv::file_and_error_handler;
-        use sqlx::postgres::PgPoolOptions;
+        use portfolio::sitemap::CrawlerConfig;
+        use portfolio::state::AppState;
+        use portfolio::store::{FileProjectStore, PgProjectStore, SharedProjectStore};
+        use std::sync::Arc;
+        use tower_http::compression::CompressionLayer;
+        use sqlx::{postgres::PgPoolOptions, PgPool};
 
         cfg_if! {
             if #[cfg(feature = "tls")] {
-                use std::path::Path as StdPath;
                 use axum_server::tls_rustls::RustlsConfig;
             } else {
                 use tokio::net::TcpListener;
@@ -23,15 +28,60 @@ cfg_if! {
 
         #[tokio::main]
         async fn main() {
-            cfg_if! {
-                if #[cfg(debug_assertions)] {
-                    let log_level = log::Level::Debug;
-                } else {
-                    let log_level = log::Level::Warn;
+            let cli = Cli::parse();
+
+            // A .env file is only a convenience for development
+            dotenvy::dotenv().ok();
+            let config = match Config::load() {
+                Ok(config) => Arc::new(config),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            simple_logger::init_with_level(config.log_level).expect("couldn't initialize logging");
+
+            // SQL connection pool, if there is a database
+            let pool = match &config.database_url {
+                Some(database_url) => Some(
+                    PgPoolOptions::new()
+                        .max_connections(config.pool_size)
+                        .connect(database_url)
+                        .await
+                        .expect("couldn't connect to the database"),
+                ),
+                None => None,
+            };
+
+            let command = match cli.command {
+                _ if cli.migrate_only => Command::Migrate,
+                Some(command) => command,
+                None => Command::Serve,
+            };
+            match command {
+                Command::Serve => {
+                    if let (Some(pool), true) = (&pool, config.run_migrations) {
+                        if let Err(e) = portfolio::db::MIGRATOR.run(pool).await {
+                            log::error!("couldn't apply migrations: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                    serve(config, pool).await;
+                }
+                command => {
+                    let Some(pool) = pool else {
+                        eprintln!("error: this command needs a database, set DATABASE_URL");
+                        std::process::exit(1);
+                    };
+                    if let Err(e) = portfolio::cli::run(command, &pool, &config).await {
+                        eprintln!("error: {e}");
+                        std::process::exit(1);
+                    }
                 }
             }
-            simple_logger::init_with_level(log_level).expect("couldn't initialize logging");
+        }
 
+        async fn serve(config: Arc<Config>, pool: Option<PgPool>) {
             // Setting get_configuration(None) means we'll be using cargo-leptos's env values
             // For deployment these variables are:
             // <https://github.com/leptos-rs/start-axum#executing-a-server-on-a-remote-machine-without-the-toolchain>
@@ -42,33 +92,117 @@ cfg_if! {
             let addr = leptos_options.site_addr;
             let routes = generate_route_list(|| view! { <App/> });
 
-            // SQL connection pool
-            let pool = PgPoolOptions::new()
-                .max_connections(4)
-                .connect(dotenv!("DATABASE_URL"))
-                .await
-                .unwrap();
+            // Create the admin account (or reset its password) when credentials are provided
+            if let (Some(pool), Some(username), Some(password)) =
+                (&pool, &config.admin_username, &config.admin_password)
+            {
+                portfolio::auth::upsert_admin_user(pool, username, password)
+                    .await
+                    .expect("couldn't create admin user");
+            }
+
+            let cache_metrics = Arc::new(CacheMetrics::default());
+
+            // Kept around for as long as the server runs, to keep watching
+            let mut _content_watcher = None;
+            let projects: SharedProjectStore = match (&config.content_dir, &pool) {
+                (Some(content_dir), _) => {
+                    let projects = match portfolio::content::load_projects(content_dir) {
+                        Ok(projects) => projects,
+                        Err(e) => {
+                            log::error!("couldn't load content: {e}");
+                            std::process::exit(1);
+                        }
+                    };
+                    if let (Some(pool), true) = (&pool, config.sync_content) {
+                        match portfolio::content::sync_projects(pool, &projects, false).await {
+                            Ok(changes) => log::info!("synced {} projects from content", changes.len()),
+                            Err(e) => {
+                                log::error!("couldn't sync content: {e}");
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                    let store = Arc::new(FileProjectStore::new(projects));
+                    if config.watch_content {
+                        match portfolio::content::watch(content_dir.clone(), store.clone()) {
+                            Ok(watcher) => _content_watcher = Some(watcher),
+                            Err(e) => log::error!("couldn't watch content for changes: {e}"),
+                        }
+                    }
+                    store
+                }
+                (None, Some(pool)) => {
+                    let store: SharedProjectStore = Arc::new(PgProjectStore::new(pool.clone()));
+                    if config.cache_ttl.is_zero() {
+                        store
+                    } else {
+                        let store: SharedProjectStore = Arc::new(CachedProjectStore::new(
+                            store,
+                            config.cache_ttl,
+                            cache_metrics.clone(),
+                        ));
+                        tokio::spawn(portfolio::cache::listen_for_changes(pool.clone(), store.clone()));
+                        store
+                    }
+                }
+                (None, None) => unreachable!("the config requires a database without a content directory"),
+            };
+
+            let state = AppState {
+                leptos_options,
+                pool: pool.clone(),
+                projects: projects.clone(),
+                crawlers: Arc::new(CrawlerConfig::new(&routes, config.robots_disallow.clone())),
+                config: config.clone(),
+            };
+
+            let context_config = config.clone();
+            let context_options = state.leptos_options.clone();
 
             // build our application with a route
             let app = Router::new()
-                .leptos_routes_with_context(&leptos_options, routes, move || {
-                    provide_context(pool.clone());
+                .merge(portfolio::uploads::routes(&config.upload_dir))
+                .merge(portfolio::markdown::routes())
+                .merge(portfolio::feeds::routes())
+                .merge(portfolio::og_image::routes())
+                .merge(portfolio::sitemap::routes())
+                .leptos_routes_with_context(&state, routes, move || {
+                    if let Some(pool) = &pool {
+                        provide_context(pool.clone());
+                    }
+                    provide_context(projects.clone());
+                    provide_context(context_config.clone());
+                    provide_context(context_options.clone());
                 }, App)
                 .fallback(file_and_error_handler)
-                .with_state(leptos_options);
+                .layer(axum::middleware::from_fn(portfolio::http_cache::http_cache))
+                // Outside of the caching layer, so ETags are computed before compression
+                .layer(CompressionLayer::new())
+                .with_state(state);
+
+            if let Some(metrics_addr) = config.metrics_addr {
+                let metrics = portfolio::cache::metrics_routes(cache_metrics);
+                let listener = tokio::net::TcpListener::bind(metrics_addr)
+                    .await
+                    .expect("couldn't bind the metrics address");
+                log::info!("serving metrics on http://{}/metrics", &metrics_addr);
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(listener, metrics).await {
+                        log::error!("metrics server stopped: {e}");
+                    }
+                });
+            }
 
             cfg_if! {
                 if #[cfg(feature = "tls")] {
-                    let config = RustlsConfig::from_pem_file(
-                        StdPath::new("ssl/cert.pem"),
-                        StdPath::new("ssl/cert.key"),
-                    )
-                    .await
-                    .unwrap();
+                    let tls_config = RustlsConfig::from_pem_file(&config.tls_cert, &config.tls_key)
+                        .await
+                        .expect("couldn't load the TLS certificate");
 
                     // run our app with axum_server's rustls server
                     log::info!("listening on https://{}", &addr);
-                    axum_server::bind_rustls(addr, config)
+                    axum_server::bind_rustls(addr, tls_config)
                         .serve(app.into_make_service())
                         .await
                         .unwrap();
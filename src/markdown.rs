@@ -0,0 +1,264 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use axum::{http::header, response::IntoResponse, routing::get, Router};
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use syntect::{
+    highlighting::ThemeSet,
+    html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+
+use crate::escape::escape;
+use crate::state::AppState;
+
+/// Highlighted tokens get classes with this prefix, styled by `/highlight.css`.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const THEME: &str = "base16-ocean.dark";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Renders Markdown into HTML that is safe to insert into the page: code fences
+/// are syntax highlighted, headings get anchors, and anything that could run
+/// scripts is stripped out.
+pub fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let events = add_heading_anchors(highlight_code_blocks(Parser::new_ext(markdown, options)));
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.into_iter());
+
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+fn sanitizer() -> &'static ammonia::Builder<'static> {
+    static SANITIZER: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = ammonia::Builder::default();
+        builder
+            .add_tag_attributes("span", ["class"])
+            .add_tag_attributes("pre", ["class"])
+            .add_tag_attributes("a", ["class"])
+            .add_tag_attributes("h1", ["id"])
+            .add_tag_attributes("h2", ["id"])
+            .add_tag_attributes("h3", ["id"])
+            .add_tag_attributes("h4", ["id"])
+            .add_tag_attributes("h5", ["id"])
+            .add_tag_attributes("h6", ["id"])
+            // Only keep the classes added by this module, so raw HTML in the
+            // Markdown can't borrow the site's styles
+            .attribute_filter(|_, attribute, value| {
+                if attribute != "class" {
+                    return Some(value.into());
+                }
+                let classes: Vec<&str> = value
+                    .split_whitespace()
+                    .filter(|class| class.starts_with("hl-") || class.starts_with("md-"))
+                    .collect();
+                (!classes.is_empty()).then(|| classes.join(" ").into())
+            });
+        builder
+    })
+}
+
+/// Replaces fenced code blocks with syntax highlighted HTML.
+fn highlight_code_blocks<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut output = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+
+    for event in events {
+        if let Some((language, code)) = &mut code_block {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => {
+                    output.push(Event::Html(highlight(language, code).into()));
+                    code_block = None;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or_default().to_owned()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((language, String::new()));
+            }
+            event => output.push(event),
+        }
+    }
+
+    output
+}
+
+fn highlight(language: &str, code: &str) -> String {
+    let syntax_set = syntax_set();
+    let syntax = syntax_set
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntax_set, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if generator
+            .parse_html_for_line_which_includes_newline(line)
+            .is_err()
+        {
+            // Fall back to the code without highlighting
+            return format!(
+                "<pre class=\"md-code\"><code>{}</code></pre>",
+                escape(code)
+            );
+        }
+    }
+
+    format!(
+        "<pre class=\"md-code\"><code>{}</code></pre>",
+        generator.finalize()
+    )
+}
+
+/// Gives every heading an id derived from its text, plus a link to itself.
+fn add_heading_anchors(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut output = Vec::with_capacity(events.len());
+    let mut used_ids: HashMap<String, usize> = HashMap::new();
+    let mut heading: Option<(HeadingLevel, Vec<Event>)> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => heading = Some((level, Vec::new())),
+            Event::End(TagEnd::Heading(_)) => {
+                let Some((level, contents)) = heading.take() else {
+                    continue;
+                };
+
+                let text: String = contents
+                    .iter()
+                    .filter_map(|event| match event {
+                        Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+                        _ => None,
+                    })
+                    .collect();
+                let id = unique_id(&mut used_ids, &text);
+
+                output.push(Event::Start(Tag::Heading {
+                    level,
+                    id: Some(CowStr::from(id.clone())),
+                    classes: Vec::new(),
+                    attrs: Vec::new(),
+                }));
+                output.push(Event::Html(
+                    format!("<a class=\"md-anchor\" href=\"#{id}\">#</a> ").into(),
+                ));
+                output.extend(contents);
+                output.push(Event::End(TagEnd::Heading(level)));
+            }
+            event => match &mut heading {
+                Some((_, contents)) => contents.push(event),
+                None => output.push(event),
+            },
+        }
+    }
+
+    output
+}
+
+fn unique_id(used_ids: &mut HashMap<String, usize>, text: &str) -> String {
+    let mut id = String::from("section");
+    for word in text
+        .to_lowercase()
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        id.push('-');
+        id.push_str(word);
+    }
+
+    let count = used_ids.entry(id.clone()).or_default();
+    *count += 1;
+    match *count {
+        1 => id,
+        n => format!("{id}-{n}"),
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/highlight.css", get(highlight_css))
+}
+
+/// Styles for the classes of highlighted code.
+async fn highlight_css() -> impl IntoResponse {
+    static CSS: OnceLock<String> = OnceLock::new();
+    let css = CSS.get_or_init(|| {
+        let themes = ThemeSet::load_defaults();
+        css_for_theme_with_class_style(&themes.themes[THEME], CLASS_STYLE).unwrap_or_else(|e| {
+            log::error!("couldn't generate highlighting styles: {e}");
+            String::new()
+        })
+    });
+
+    ([(header::CONTENT_TYPE, "text/css")], css.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_scripts() {
+        let html = render("Hello\n\n<script>alert(1)</script>\n");
+        assert!(!html.contains("<script"), "{html}");
+        assert!(!html.contains("alert"), "{html}");
+    }
+
+    #[test]
+    fn strips_javascript_links() {
+        let html = render("[click](javascript:alert(1)) <a href=\"javascript:alert(2)\">me</a>");
+        assert!(!html.contains("javascript:"), "{html}");
+        assert!(html.contains("click"), "{html}");
+    }
+
+    #[test]
+    fn strips_event_handlers() {
+        let html =
+            render("<img src=\"/a.png\" onerror=\"alert(1)\"> <p onclick=\"alert(2)\">hi</p>");
+        assert!(!html.contains("onerror"), "{html}");
+        assert!(!html.contains("onclick"), "{html}");
+        assert!(!html.contains("alert"), "{html}");
+    }
+
+    #[test]
+    fn keeps_only_its_own_classes() {
+        let html = render(concat!(
+            "<span class=\"admin-only hl-keyword md-note\">x</span> ",
+            "<a class=\"button\" href=\"/\">y</a>",
+        ));
+        assert!(html.contains("class=\"hl-keyword md-note\""), "{html}");
+        assert!(!html.contains("admin-only"), "{html}");
+        assert!(!html.contains("button"), "{html}");
+    }
+
+    #[test]
+    fn highlighted_code_keeps_its_classes() {
+        let html = render("```rust\nfn main() {}\n```\n");
+        assert!(html.contains("<pre class=\"md-code\">"), "{html}");
+        assert!(html.contains("class=\"hl-"), "{html}");
+        assert!(html.contains("main"), "{html}");
+    }
+
+    #[test]
+    fn duplicate_headings_get_unique_ids() {
+        let html = render("# Setup\n\n## Setup\n\n### Setup!\n");
+        assert!(html.contains("<h1 id=\"section-setup\">"), "{html}");
+        assert!(html.contains("<h2 id=\"section-setup-2\">"), "{html}");
+        assert!(html.contains("<h3 id=\"section-setup-3\">"), "{html}");
+        assert!(html.contains("href=\"#section-setup-2\""), "{html}");
+    }
+}
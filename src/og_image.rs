@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Path as UrlPath, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use leptos::LeptosOptions;
+use resvg::{tiny_skia, usvg};
+
+use crate::app::projects::{icon_from_key, Project};
+use crate::escape::escape;
+use crate::state::AppState;
+use crate::store::SharedProjectStore;
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const MAX_CACHED_IMAGES: usize = 256;
+/// Lines of the short description that fit under the name.
+const DESCRIPTION_LINES: usize = 4;
+const DESCRIPTION_LINE_CHARS: usize = 48;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/og/projects/:file", get(project_image))
+}
+
+/// Rendered cards, keyed by [`Project::content_hash`] so an edited project gets
+/// a new card while unchanged ones are only rendered once.
+fn cache() -> &'static Mutex<HashMap<u64, Bytes>> {
+    static CACHE: OnceLock<Mutex<HashMap<u64, Bytes>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// The site's fonts, so cards use the same typeface as the pages.
+fn font_db(site_root: &str) -> Arc<usvg::fontdb::Database> {
+    static FONT_DB: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONT_DB
+        .get_or_init(|| {
+            let mut db = usvg::fontdb::Database::new();
+            db.load_fonts_dir(Path::new(site_root).join("fonts"));
+            Arc::new(db)
+        })
+        .clone()
+}
+
+async fn project_image(
+    State(store): State<SharedProjectStore>,
+    State(options): State<LeptosOptions>,
+    UrlPath(file): UrlPath<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let not_found = || (StatusCode::NOT_FOUND, "Project not found".to_owned());
+
+    let slug = file.strip_suffix(".png").ok_or_else(not_found)?;
+    let project = store
+        .project_by_slug(slug)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(not_found)?;
+
+    let hash = project.content_hash();
+    let cached = cache().lock().unwrap().get(&hash).cloned();
+    let png = match cached {
+        Some(png) => png,
+        None => {
+            let fonts = font_db(&options.site_root);
+            let png = tokio::task::spawn_blocking(move || render(&project, fonts))
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+            let mut cache = cache().lock().unwrap();
+            if cache.len() >= MAX_CACHED_IMAGES {
+                cache.clear();
+            }
+            cache.insert(hash, png.clone());
+            png
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        png,
+    ))
+}
+
+fn render(project: &Project, fonts: Arc<usvg::fontdb::Database>) -> Result<Bytes, String> {
+    let options = usvg::Options {
+        fontdb: fonts,
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(&card_svg(project), &options).map_err(|e| e.to_string())?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(WIDTH, HEIGHT).ok_or("invalid image size")?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap.encode_png().map(Bytes::from).map_err(|e| e.to_string())
+}
+
+/// The card as SVG: the background gradient of the site, the project's name and
+/// short description, and a row of its technology icons.
+fn card_svg(project: &Project) -> String {
+    let description: String = wrap(&project.short_description, DESCRIPTION_LINE_CHARS)
+        .iter()
+        .take(DESCRIPTION_LINES)
+        .enumerate()
+        .map(|(i, line)| {
+            format!(
+                r#"<text x="80" y="{}" font-size="36" fill="#fffaff">{}</text>"#,
+                290 + i * 48,
+                escape(line)
+            )
+        })
+        .collect();
+
+    let icons: String = project
+        .technologies
+        .iter()
+        .filter_map(|technology| icon_from_key(technology.icon.as_deref()?))
+        .enumerate()
+        .map(|(i, icon)| {
+            format!(
+                r#"<svg x="{}" y="500" width="56" height="56" viewBox="{}" fill="{}" stroke="{}">{}</svg>"#,
+                80 + i * 80,
+                icon.view_box.unwrap_or("0 0 24 24"),
+                icon.fill.unwrap_or("#fffaff"),
+                icon.stroke.unwrap_or("none"),
+                icon.data
+            )
+        })
+        .collect();
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="Ubuntu Mono">
+    <defs>
+        <radialGradient id="Gradient1">
+            <stop offset="0%" stop-color="#00ff61"/>
+            <stop offset="100%" stop-color="#00732c"/>
+        </radialGradient>
+    </defs>
+    <rect width="100%" height="100%" fill="black"/>
+    <ellipse cx="85%" cy="10%" rx="480" ry="240" fill="url(#Gradient1)" opacity="0.6"/>
+    <rect x="40" y="40" width="{}" height="{}" rx="24" fill="#262B2B" fill-opacity="0.85"/>
+    <text x="80" y="200" font-size="80" font-weight="bold" fill="#09e85e">{}</text>
+    {description}
+    <g fill="#fffaff" color="#fffaff">{icons}</g>
+    <text x="{}" y="556" font-size="32" fill="#686963" text-anchor="end">codeboi.dev</text>
+</svg>"##,
+        WIDTH - 80,
+        HEIGHT - 80,
+        escape(&project.name),
+        WIDTH - 80,
+    )
+}
+
+/// Splits text into lines of at most `width` characters, breaking between words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_owned()),
+        }
+    }
+    lines
+}
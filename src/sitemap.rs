@@ -0,0 +1,109 @@
+use std::{fmt::Write, sync::Arc};
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use leptos_router::RouteListing;
+use sqlx::PgPool;
+
+use crate::app::SITE_URL;
+use crate::escape::escape;
+use crate::state::AppState;
+use crate::store::SharedProjectStore;
+
+/// Paths crawlers are pointed at by the sitemap and kept away from by robots.txt.
+pub struct CrawlerConfig {
+    /// App routes without parameters, e.g. `/projects` but not `/projects/:slug`.
+    pub static_paths: Vec<String>,
+    /// Path prefixes listed as `Disallow` in robots.txt and left out of the sitemap.
+    pub disallow: Vec<String>,
+}
+
+impl CrawlerConfig {
+    pub fn new(routes: &[RouteListing], disallow: Vec<String>) -> Self {
+        let static_paths = routes
+            .iter()
+            .map(|route| route.path().to_owned())
+            .filter(|path| !path.contains(':') && !path.contains('*'))
+            .filter(|path| !disallow.iter().any(|prefix| path.starts_with(prefix.as_str())))
+            .collect();
+
+        Self {
+            static_paths,
+            disallow,
+        }
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/sitemap.xml", get(sitemap))
+        .route("/robots.txt", get(robots))
+}
+
+fn push_url(xml: &mut String, path: &str, lastmod: Option<time::Date>) {
+    let _ = write!(xml, "<url><loc>{}</loc>", escape(&format!("{SITE_URL}{path}")));
+    if let Some(lastmod) = lastmod {
+        let _ = write!(xml, "<lastmod>{lastmod}</lastmod>");
+    }
+    xml.push_str("</url>");
+}
+
+async fn sitemap(
+    State(store): State<SharedProjectStore>,
+    State(pool): State<Option<PgPool>>,
+    State(crawlers): State<Arc<CrawlerConfig>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let projects = store.projects().await.map_err(internal_error)?;
+    // Posts only exist in the database
+    let posts = match &pool {
+        Some(pool) => crate::db::get_posts(pool, None)
+            .await
+            .map_err(internal_error)?,
+        None => Vec::new(),
+    };
+
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?><urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+    );
+    for path in &crawlers.static_paths {
+        push_url(&mut xml, path, None);
+    }
+    for project in &projects {
+        push_url(
+            &mut xml,
+            &format!("/projects/{}", project.slug),
+            Some(project.date_created),
+        );
+    }
+    for post in &posts {
+        push_url(&mut xml, &format!("/blog/{}", post.slug), Some(post.date()));
+    }
+    xml.push_str("</urlset>");
+
+    Ok(([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], xml))
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Something went wrong: {e}"),
+    )
+}
+
+async fn robots(State(crawlers): State<Arc<CrawlerConfig>>) -> impl IntoResponse {
+    let mut robots = String::from("User-agent: *\n");
+    if crawlers.disallow.is_empty() {
+        robots.push_str("Allow: /\n");
+    }
+    for path in &crawlers.disallow {
+        let _ = writeln!(robots, "Disallow: {path}");
+    }
+    let _ = writeln!(robots, "\nSitemap: {SITE_URL}/sitemap.xml");
+
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], robots)
+}
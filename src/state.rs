@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use leptos::LeptosOptions;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::sitemap::CrawlerConfig;
+use crate::store::SharedProjectStore;
+
+/// State shared by the axum handlers that live outside of Leptos.
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    pub leptos_options: LeptosOptions,
+    /// Missing when projects are served from files without a database.
+    pub pool: Option<PgPool>,
+    pub projects: SharedProjectStore,
+    pub crawlers: Arc<CrawlerConfig>,
+    pub config: Arc<Config>,
+}
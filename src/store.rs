@@ -0,0 +1,191 @@
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use thiserror::Error;
+
+use crate::app::projects::{Project, ProjectCursor, Technology};
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Where the public pages read projects from, chosen at startup. Projects are
+/// always ordered newest first.
+#[async_trait]
+pub trait ProjectStore: Send + Sync {
+    async fn projects(&self) -> Result<Vec<Project>, StoreError>;
+
+    async fn pinned_projects(&self) -> Result<Vec<Project>, StoreError>;
+
+    async fn project_by_slug(&self, slug: &str) -> Result<Option<Project>, StoreError>;
+
+    async fn project_by_id(&self, id: i32) -> Result<Option<Project>, StoreError>;
+
+    /// Projects using the technology `tech` and matching the search `query`,
+    /// starting after the cursor. Filters that are `None` are ignored.
+    async fn search_projects(
+        &self,
+        tech: Option<&str>,
+        query: Option<&str>,
+        after: Option<ProjectCursor>,
+        limit: usize,
+    ) -> Result<Vec<Project>, StoreError>;
+
+    /// Every technology used by at least one project, ordered by name.
+    async fn technologies(&self) -> Result<Vec<Technology>, StoreError>;
+
+    /// Called after projects change, for stores that keep copies of them.
+    fn invalidate(&self) {}
+}
+
+pub type SharedProjectStore = Arc<dyn ProjectStore>;
+
+/// Projects in the `projects` table.
+pub struct PgProjectStore {
+    pool: PgPool,
+}
+
+impl PgProjectStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProjectStore for PgProjectStore {
+    async fn projects(&self) -> Result<Vec<Project>, StoreError> {
+        Ok(crate::db::get_projects(&self.pool).await?)
+    }
+
+    async fn pinned_projects(&self) -> Result<Vec<Project>, StoreError> {
+        Ok(crate::db::get_pinned_projects(&self.pool).await?)
+    }
+
+    async fn project_by_slug(&self, slug: &str) -> Result<Option<Project>, StoreError> {
+        Ok(crate::db::get_project_by_slug(&self.pool, slug).await?)
+    }
+
+    async fn project_by_id(&self, id: i32) -> Result<Option<Project>, StoreError> {
+        Ok(crate::db::get_project_by_id(&self.pool, id).await?)
+    }
+
+    async fn search_projects(
+        &self,
+        tech: Option<&str>,
+        query: Option<&str>,
+        after: Option<ProjectCursor>,
+        limit: usize,
+    ) -> Result<Vec<Project>, StoreError> {
+        Ok(crate::db::search_projects(&self.pool, tech, query, after, limit).await?)
+    }
+
+    async fn technologies(&self) -> Result<Vec<Technology>, StoreError> {
+        Ok(crate::db::get_technologies(&self.pool).await?)
+    }
+}
+
+/// Projects read from a content directory, see [`crate::content::load_projects`].
+/// Requests read from a snapshot, which is swapped out when the content changes.
+pub struct FileProjectStore {
+    snapshot: RwLock<Arc<Vec<Project>>>,
+}
+
+impl FileProjectStore {
+    pub fn new(projects: Vec<Project>) -> Self {
+        Self {
+            snapshot: RwLock::new(Arc::new(projects)),
+        }
+    }
+
+    fn snapshot(&self) -> Arc<Vec<Project>> {
+        self.snapshot.read().unwrap().clone()
+    }
+
+    /// Serves `projects` from now on. Requests in progress keep the old ones.
+    pub fn replace(&self, projects: Vec<Project>) {
+        *self.snapshot.write().unwrap() = Arc::new(projects);
+    }
+}
+
+#[async_trait]
+impl ProjectStore for FileProjectStore {
+    async fn projects(&self) -> Result<Vec<Project>, StoreError> {
+        Ok(self.snapshot().to_vec())
+    }
+
+    async fn pinned_projects(&self) -> Result<Vec<Project>, StoreError> {
+        Ok(self
+            .snapshot()
+            .iter()
+            .filter(|project| project.pinned)
+            .cloned()
+            .collect())
+    }
+
+    async fn project_by_slug(&self, slug: &str) -> Result<Option<Project>, StoreError> {
+        Ok(self
+            .snapshot()
+            .iter()
+            .find(|project| project.slug == slug)
+            .cloned())
+    }
+
+    async fn project_by_id(&self, id: i32) -> Result<Option<Project>, StoreError> {
+        Ok(self.snapshot().iter().find(|project| project.id == id).cloned())
+    }
+
+    async fn search_projects(
+        &self,
+        tech: Option<&str>,
+        query: Option<&str>,
+        after: Option<ProjectCursor>,
+        limit: usize,
+    ) -> Result<Vec<Project>, StoreError> {
+        let words: Vec<String> = query
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+
+        Ok(self
+            .snapshot()
+            .iter()
+            .filter(|project| {
+                after.map_or(true, |cursor| {
+                    (project.date_created, project.id) < (cursor.date_created, cursor.id)
+                })
+            })
+            .filter(|project| {
+                tech.map_or(true, |tech| {
+                    project.technologies.iter().any(|technology| technology.slug == tech)
+                })
+            })
+            .filter(|project| {
+                let text = format!(
+                    "{} {} {}",
+                    project.name, project.short_description, project.long_description
+                )
+                .to_lowercase();
+                words.iter().all(|word| text.contains(word.as_str()))
+            })
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn technologies(&self) -> Result<Vec<Technology>, StoreError> {
+        let mut technologies: Vec<Technology> = Vec::new();
+        let projects = self.snapshot();
+        for technology in projects.iter().flat_map(|project| &project.technologies) {
+            if !technologies.iter().any(|known| known.slug == technology.slug) {
+                technologies.push(technology.clone());
+            }
+        }
+        technologies.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(technologies)
+    }
+}
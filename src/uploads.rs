@@ -0,0 +1,233 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
+    path::Path as FsPath,
+    sync::Arc,
+};
+
+use axum::{
+    extract::{multipart::MultipartError, DefaultBodyLimit, Multipart, Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::post,
+    Router,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use sqlx::PgPool;
+use thiserror::Error;
+use tower_http::services::ServeDir;
+
+use crate::app::projects::ImageVariant;
+use crate::config::Config;
+use crate::state::AppState;
+use crate::store::SharedProjectStore;
+
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+/// URL prefix the upload directory is served under.
+const UPLOADS_PATH: &str = "/uploads";
+
+/// Widths every uploaded image is resized to, as long as it is at least that wide.
+const VARIANT_WIDTHS: [u32; 3] = [480, 960, 1600];
+const VARIANT_FORMATS: [(ImageFormat, &str); 3] = [
+    (ImageFormat::Png, "png"),
+    (ImageFormat::WebP, "webp"),
+    (ImageFormat::Avif, "avif"),
+];
+/// Size of the box the inline placeholder is shrunk to fit in.
+const PLACEHOLDER_SIZE: u32 = 16;
+
+#[derive(Debug, Error)]
+pub enum UploadError {
+    #[error("Not logged in")]
+    Unauthorized,
+    #[error("Uploads need a database")]
+    NoDatabase,
+    #[error("Project not found")]
+    ProjectNotFound,
+    #[error("No image was uploaded")]
+    MissingImage,
+    #[error("Images must be PNG, JPEG or WebP")]
+    UnsupportedType,
+    #[error("Images must be smaller than {} MiB", MAX_UPLOAD_BYTES / 1024 / 1024)]
+    TooLarge,
+    #[error(transparent)]
+    Multipart(#[from] MultipartError),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Task(#[from] tokio::task::JoinError),
+}
+
+impl IntoResponse for UploadError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            UploadError::Unauthorized => StatusCode::UNAUTHORIZED,
+            UploadError::NoDatabase => StatusCode::SERVICE_UNAVAILABLE,
+            UploadError::ProjectNotFound => StatusCode::NOT_FOUND,
+            UploadError::MissingImage | UploadError::Image(_) => StatusCode::BAD_REQUEST,
+            UploadError::UnsupportedType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            UploadError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            UploadError::Multipart(ref e) => e.status(),
+            UploadError::Io(_) | UploadError::Database(_) | UploadError::Task(_) => {
+                log::error!("image upload failed: {self}");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        (status, self.to_string()).into_response()
+    }
+}
+
+pub fn routes(upload_dir: &FsPath) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/admin/projects/:id/image",
+            post(upload_project_image).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)),
+        )
+        .nest_service(UPLOADS_PATH, ServeDir::new(upload_dir))
+}
+
+/// Accepts a multipart upload with an `image` field, processes it into every
+/// variant and makes them the project's image.
+async fn upload_project_image(
+    State(pool): State<Option<PgPool>>,
+    State(store): State<SharedProjectStore>,
+    State(config): State<Arc<Config>>,
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Redirect, UploadError> {
+    let pool = pool.ok_or(UploadError::NoDatabase)?;
+    let token = crate::auth::session_token_from(&headers).ok_or(UploadError::Unauthorized)?;
+    crate::auth::admin_for_token(&pool, &token)
+        .await?
+        .ok_or(UploadError::Unauthorized)?;
+
+    let project = crate::db::get_project_by_id(&pool, id)
+        .await?
+        .ok_or(UploadError::ProjectNotFound)?;
+
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("image") {
+            if !matches!(
+                field.content_type(),
+                Some("image/png" | "image/jpeg" | "image/webp")
+            ) {
+                return Err(UploadError::UnsupportedType);
+            }
+            upload = Some(field.bytes().await?);
+        }
+    }
+    let upload = upload.ok_or(UploadError::MissingImage)?;
+    if upload.len() > MAX_UPLOAD_BYTES {
+        return Err(UploadError::TooLarge);
+    }
+
+    // Don't trust the declared content type alone
+    let format = image::guess_format(&upload).map_err(|_| UploadError::UnsupportedType)?;
+    if !matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP) {
+        return Err(UploadError::UnsupportedType);
+    }
+
+    let slug = project.slug.clone();
+    let upload_dir = config.upload_dir.clone();
+    let (images, placeholder) = tokio::task::spawn_blocking(move || {
+        // Not every encoder supports every pixel format, but all of them take RGBA
+        let image = image::load_from_memory_with_format(&upload, format)?;
+        let image = DynamicImage::ImageRgba8(image.into_rgba8());
+        Ok::<_, UploadError>((
+            write_variants(&image, &upload, &upload_dir, &slug)?,
+            placeholder(&image)?,
+        ))
+    })
+    .await??;
+
+    let old_images =
+        crate::db::replace_project_images(&pool, id, &images, Some(&placeholder)).await?;
+    store.invalidate();
+    for old_image in old_images {
+        if images.iter().any(|image| image.path == old_image.path) {
+            continue;
+        }
+        // Only files in the upload directory are ours to remove
+        if let Some(file_name) = old_image
+            .path
+            .strip_prefix(UPLOADS_PATH)
+            .and_then(|path| path.strip_prefix('/'))
+        {
+            let path = config.upload_dir.join(file_name);
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                log::warn!("couldn't remove old image {}: {e}", path.display());
+            }
+        }
+    }
+
+    Ok(Redirect::to(&format!("/admin/projects/{id}")))
+}
+
+/// Resizes `image` to every variant width and format, writes them to the upload
+/// directory and returns what was written.
+fn write_variants(
+    image: &DynamicImage,
+    original: &[u8],
+    upload_dir: &FsPath,
+    slug: &str,
+) -> Result<Vec<ImageVariant>, UploadError> {
+    // Files are named after the upload's content, so a new upload never reuses
+    // the URL of an older one
+    let mut hasher = DefaultHasher::new();
+    original.hash(&mut hasher);
+    let version = format!("{:016x}", hasher.finish());
+
+    let directory = format!("projects/{slug}");
+    std::fs::create_dir_all(upload_dir.join(&directory))?;
+
+    let mut widths: Vec<u32> = VARIANT_WIDTHS
+        .into_iter()
+        .filter(|width| *width <= image.width())
+        .collect();
+    if widths.is_empty() {
+        widths.push(image.width());
+    }
+
+    let mut variants = Vec::new();
+    for width in widths {
+        let resized = image.resize(width, u32::MAX, FilterType::Lanczos3);
+
+        for (format, extension) in VARIANT_FORMATS {
+            let file_name = format!("{directory}/{width}-{version}.{extension}");
+            let mut encoded = Cursor::new(Vec::new());
+            resized.write_to(&mut encoded, format)?;
+            std::fs::write(upload_dir.join(&file_name), encoded.into_inner())?;
+
+            variants.push(ImageVariant {
+                width: resized.width() as i32,
+                height: resized.height() as i32,
+                format: extension.to_owned(),
+                path: format!("{UPLOADS_PATH}/{file_name}"),
+            });
+        }
+    }
+
+    Ok(variants)
+}
+
+/// A tiny version of `image` as a data URI, small enough to inline into the page
+/// and be stretched into a blurry preview.
+fn placeholder(image: &DynamicImage) -> Result<String, UploadError> {
+    let mut encoded = Cursor::new(Vec::new());
+    image
+        .thumbnail(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE)
+        .write_to(&mut encoded, ImageFormat::Png)?;
+
+    Ok(format!(
+        "data:image/png;base64,{}",
+        BASE64.encode(encoded.into_inner())
+    ))
+}